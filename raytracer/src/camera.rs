@@ -1,18 +1,320 @@
-use crate::math::{degrees_to_radians, Vec3, R};
-use crate::ray::Ray;
+use raytracer_core::math::{degrees_to_radians, random_normalized, Vec3, PI, R};
+use raytracer_core::ray::Ray;
+use raytracer_core::render::RayCaster;
+use serde::{Deserialize, Serialize};
 
-pub struct Camera {
+// Turns a point on the image into a ray, (0, 0) being the bottom left corner and (1, 1) the top
+// right
+pub trait Camera: Sync {
+    fn get_ray(&self, u: R, v: R) -> Ray;
+}
+
+impl RayCaster for Box<dyn Camera> {
+    fn ray(&self, u: R, v: R) -> Ray {
+        self.get_ray(u, v)
+    }
+}
+
+// The camera part of the scene file
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CameraSettings {
+    pub eye: Vec3,
+    pub target: Vec3,
+    pub up_dir: Vec3,
+    pub model: CameraModel,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub enum CameraModel {
+    // Focused on the target unless focus_dist is set. A lens replaces v_fov_degrees and aperture
+    // with those of a real one.
+    ThinLens {
+        #[serde(default = "default_v_fov_degrees")]
+        v_fov_degrees: R,
+        #[serde(default = "default_aperture")]
+        aperture: R,
+        focus_dist: Option<R>,
+        lens: Option<PhysicalLens>,
+        #[serde(default)]
+        bokeh: Bokeh,
+    },
+    Orthographic {
+        width: R,
+    },
+    Fisheye {
+        fov_degrees: R,
+    },
+    Equirectangular,
+}
+
+fn default_v_fov_degrees() -> R {
+    20.
+}
+
+fn default_aperture() -> R {
+    2.
+}
+
+// The final image of Ray Tracing in One Weekend
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            eye: Vec3::new(3., 3., 2.),
+            target: Vec3::new(0., 0., -1.),
+            up_dir: Vec3::new(0., 1., 0.),
+            model: CameraModel::ThinLens {
+                v_fov_degrees: default_v_fov_degrees(),
+                aperture: default_aperture(),
+                focus_dist: None,
+                lens: None,
+                bokeh: Bokeh::Circle,
+            },
+        }
+    }
+}
+
+impl CameraSettings {
+    // Describes every setting that would produce a degenerate camera
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = vec![];
+
+        match self.model {
+            CameraModel::ThinLens {
+                v_fov_degrees,
+                aperture,
+                focus_dist,
+                lens,
+                bokeh,
+            } => {
+                if v_fov_degrees <= 0. || v_fov_degrees >= 180. {
+                    errors.push(format!(
+                        "v_fov_degrees must be between 0 and 180, got {}",
+                        v_fov_degrees
+                    ));
+                }
+                if aperture < 0. {
+                    errors.push(format!("aperture must not be negative, got {}", aperture));
+                }
+                if let Some(focus_dist) = focus_dist {
+                    if focus_dist <= 0. {
+                        errors.push(format!("focus_dist must be positive, got {}", focus_dist));
+                    }
+                }
+                if let Some(lens) = lens {
+                    for (name, value) in &[
+                        ("focal_length_mm", lens.focal_length_mm),
+                        ("f_stop", lens.f_stop),
+                        ("sensor_width_mm", lens.sensor_width_mm),
+                    ] {
+                        if *value <= 0. {
+                            errors.push(format!("lens {} must be positive, got {}", name, value));
+                        }
+                    }
+                }
+                if let Bokeh::Polygon { blades, .. } = bokeh {
+                    if blades < 3 {
+                        errors.push(format!("bokeh needs at least 3 blades, got {}", blades));
+                    }
+                }
+            }
+            CameraModel::Orthographic { width } => {
+                if width <= 0. {
+                    errors.push(format!(
+                        "orthographic width must be positive, got {}",
+                        width
+                    ));
+                }
+            }
+            CameraModel::Fisheye { fov_degrees } => {
+                if fov_degrees <= 0. || fov_degrees > 360. {
+                    errors.push(format!(
+                        "fisheye fov_degrees must be between 0 and 360, got {}",
+                        fov_degrees
+                    ));
+                }
+            }
+            CameraModel::Equirectangular => {}
+        }
+
+        let view_dir = self.target - self.eye;
+        if view_dir.len() == 0. {
+            errors.push("eye and target must be different points".to_string());
+        } else if self.up_dir.len() == 0. {
+            errors.push("up_dir must not be zero".to_string());
+        } else if view_dir
+            .unit_vector()
+            .cross(self.up_dir.unit_vector())
+            .len()
+            <= 1e-4
+        {
+            errors.push("up_dir must not be parallel to the view direction".to_string());
+        }
+
+        errors
+    }
+
+    pub fn into_camera(&self, aspect_ratio: R) -> Box<dyn Camera> {
+        let (eye, target, up_dir) = (self.eye, self.target, self.up_dir);
+        match self.model {
+            CameraModel::ThinLens {
+                v_fov_degrees,
+                aperture,
+                focus_dist,
+                lens,
+                bokeh,
+            } => {
+                let focus_dist = focus_dist.unwrap_or_else(|| (eye - target).len());
+                let camera = match lens {
+                    Some(lens) => {
+                        ThinLens::physical(eye, target, up_dir, aspect_ratio, lens, focus_dist)
+                    }
+                    None => ThinLens::new(
+                        eye,
+                        target,
+                        up_dir,
+                        v_fov_degrees,
+                        aspect_ratio,
+                        aperture,
+                        focus_dist,
+                    ),
+                };
+                Box::new(camera.with_bokeh(bokeh))
+            }
+            CameraModel::Orthographic { width } => {
+                Box::new(Orthographic::new(eye, target, up_dir, width, aspect_ratio))
+            }
+            CameraModel::Fisheye { fov_degrees } => {
+                Box::new(Fisheye::new(eye, target, up_dir, fov_degrees, aspect_ratio))
+            }
+            CameraModel::Equirectangular => Box::new(Equirectangular::new(eye, target, up_dir)),
+        }
+    }
+
+    // The left and right eyes, moved apart along the camera's right and still looking the same way
+    pub fn stereo_pair(&self, interocular_distance: R) -> (Self, Self) {
+        let offset = View::new(self.eye, self.target, self.up_dir).u * (interocular_distance / 2.);
+        let moved = |offset: Vec3| Self {
+            eye: self.eye + offset,
+            target: self.target + offset,
+            ..*self
+        };
+
+        (moved(-offset), moved(offset))
+    }
+}
+
+// Where the camera is and which way it looks: u is right, v up and w backwards
+#[derive(Copy, Clone, Debug)]
+struct View {
+    origin: Vec3,
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
+}
+
+impl View {
+    fn new(eye: Vec3, target: Vec3, up_dir: Vec3) -> Self {
+        let w = (eye - target).unit_vector();
+        let u = up_dir.cross(w).unit_vector();
+        let v = w.cross(u);
+
+        Self {
+            origin: eye,
+            u,
+            v,
+            w,
+        }
+    }
+
+    fn direction(&self, right: R, up: R, forward: R) -> Vec3 {
+        self.u * right + self.v * up - self.w * forward
+    }
+}
+
+// Shape of the lens opening, which is the shape out of focus highlights take
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub enum Bokeh {
+    #[default]
+    Circle,
+    // Regular polygon made by that many aperture blades, at least 3, with one corner rotated from
+    // straight up
+    Polygon {
+        blades: u32,
+        rotation_degrees: R,
+    },
+}
+
+impl Bokeh {
+    // Point on the lens, in a circle of radius 1
+    fn sample(&self) -> (R, R) {
+        match *self {
+            Bokeh::Circle => {
+                let p = Vec3::random_in_unit_disk();
+                (p.x, p.y)
+            }
+            Bokeh::Polygon {
+                blades,
+                rotation_degrees,
+            } => {
+                // Uniform in one of the triangles between the center and two neighbouring corners
+                let corner = |i: u32| {
+                    let angle = degrees_to_radians(rotation_degrees)
+                        + PI / 2.
+                        + 2. * PI * i as R / blades as R;
+                    (angle.cos(), angle.sin())
+                };
+                let i = ((random_normalized() * blades as R) as u32).min(blades - 1);
+                let (a, b) = (corner(i), corner(i + 1));
+
+                let (mut s, mut t) = (random_normalized(), random_normalized());
+                if s + t > 1. {
+                    s = 1. - s;
+                    t = 1. - t;
+                }
+                (a.0 * s + b.0 * t, a.1 * s + b.1 * t)
+            }
+        }
+    }
+}
+
+// Lens of a real camera. Scene units are meters.
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PhysicalLens {
+    pub focal_length_mm: R,
+    pub f_stop: R,
+    // The image fits the sensor's width, 36 for full frame
+    pub sensor_width_mm: R,
+}
+
+impl PhysicalLens {
+    pub fn v_fov_degrees(&self, aspect_ratio: R) -> R {
+        let sensor_height = self.sensor_width_mm / aspect_ratio;
+        (2. * (sensor_height / (2. * self.focal_length_mm)).atan()).to_degrees()
+    }
+
+    // Diameter of the opening, in meters
+    pub fn aperture(&self) -> R {
+        self.focal_length_mm / self.f_stop / 1000.
+    }
+}
+
+pub struct ThinLens {
     origin: Vec3,
     lower_left_corner: Vec3,
     horizontal: Vec3,
     vertical: Vec3,
     u: Vec3,
     v: Vec3,
-    w: Vec3,
     lens_radius: R,
+    bokeh: Bokeh,
 }
 
-impl Camera {
+impl ThinLens {
+    // Everything focus_dist away from the eye is sharp, the rest is blurrier the wider the aperture
     pub fn new(
         eye: Vec3,
         target: Vec3,
@@ -20,7 +322,7 @@ impl Camera {
         v_fov_degrees: R,
         aspect_ratio: R,
         aperture: R,
-        depth_of_field: R,
+        focus_dist: R,
     ) -> Self {
         let theta = degrees_to_radians(v_fov_degrees);
         let h = (theta / 2.).tan();
@@ -28,32 +330,58 @@ impl Camera {
         let viewport_height = 2.0 * h;
         let viewport_width = aspect_ratio * viewport_height;
 
-        let w = (eye - target).unit_vector();
-        let u = (up_dir.cross(w)).unit_vector();
-        let v = w.cross(u);
+        let View { origin, u, v, w } = View::new(eye, target, up_dir);
 
-        let origin = eye;
-        let horizontal = depth_of_field * viewport_width * u;
-        let vertical = depth_of_field * viewport_height * v;
-        let lower_left_corner = origin - horizontal / 2. - vertical / 2. - depth_of_field * w;
+        let horizontal = focus_dist * viewport_width * u;
+        let vertical = focus_dist * viewport_height * v;
+        let lower_left_corner = origin - horizontal / 2. - vertical / 2. - focus_dist * w;
 
-        let lens_radius = depth_of_field / 2.;
+        let lens_radius = aperture / 2.;
 
         Self {
             origin,
             lower_left_corner,
             horizontal,
             vertical,
-            w,
             u,
             v,
             lens_radius,
+            bokeh: Bokeh::Circle,
+        }
+    }
+
+    pub fn physical(
+        eye: Vec3,
+        target: Vec3,
+        up_dir: Vec3,
+        aspect_ratio: R,
+        lens: PhysicalLens,
+        focus_dist: R,
+    ) -> Self {
+        Self::new(
+            eye,
+            target,
+            up_dir,
+            lens.v_fov_degrees(aspect_ratio),
+            aspect_ratio,
+            lens.aperture(),
+            focus_dist,
+        )
+    }
+
+    pub fn with_bokeh(mut self, bokeh: Bokeh) -> Self {
+        if let Bokeh::Polygon { blades, .. } = bokeh {
+            assert!(blades >= 3, "bokeh needs at least 3 blades, got {}", blades);
         }
+        self.bokeh = bokeh;
+        self
     }
+}
 
-    pub fn get_ray(&self, u: R, v: R) -> Ray {
-        let rd = self.lens_radius * Vec3::random_in_unit_disk();
-        let offset = self.u * rd.x + self.v * rd.y;
+impl Camera for ThinLens {
+    fn get_ray(&self, u: R, v: R) -> Ray {
+        let (x, y) = self.bokeh.sample();
+        let offset = self.lens_radius * (self.u * x + self.v * y);
 
         Ray::new(
             self.origin + offset,
@@ -61,3 +389,581 @@ impl Camera {
         )
     }
 }
+
+// Parallel rays from a viewport width wide, centered on the eye
+pub struct Orthographic {
+    lower_left_corner: Vec3,
+    horizontal: Vec3,
+    vertical: Vec3,
+    direction: Vec3,
+}
+
+impl Orthographic {
+    pub fn new(eye: Vec3, target: Vec3, up_dir: Vec3, width: R, aspect_ratio: R) -> Self {
+        let view = View::new(eye, target, up_dir);
+        let horizontal = width * view.u;
+        let vertical = (width / aspect_ratio) * view.v;
+
+        Self {
+            lower_left_corner: eye - horizontal / 2. - vertical / 2.,
+            horizontal,
+            vertical,
+            direction: -view.w,
+        }
+    }
+}
+
+impl Camera for Orthographic {
+    fn get_ray(&self, u: R, v: R) -> Ray {
+        Ray::new(
+            self.lower_left_corner + u * self.horizontal + v * self.vertical,
+            self.direction,
+        )
+    }
+}
+
+// Equidistant fisheye: the angle from the view direction grows evenly with the distance from the
+// center of the image, reaching half the fov at the edges of its shorter side
+pub struct Fisheye {
+    view: View,
+    half_fov: R,
+    aspect_ratio: R,
+}
+
+impl Fisheye {
+    pub fn new(eye: Vec3, target: Vec3, up_dir: Vec3, fov_degrees: R, aspect_ratio: R) -> Self {
+        Self {
+            view: View::new(eye, target, up_dir),
+            half_fov: degrees_to_radians(fov_degrees) / 2.,
+            aspect_ratio,
+        }
+    }
+}
+
+impl Camera for Fisheye {
+    fn get_ray(&self, u: R, v: R) -> Ray {
+        let (x, y) = (2. * u - 1., 2. * v - 1.);
+        let (x, y) = if self.aspect_ratio >= 1. {
+            (x * self.aspect_ratio, y)
+        } else {
+            (x, y / self.aspect_ratio)
+        };
+
+        let r = (x * x + y * y).sqrt();
+        let theta = r * self.half_fov;
+        let (x, y) = if r > 0. { (x / r, y / r) } else { (0., 0.) };
+
+        Ray::new(
+            self.view.origin,
+            self.view
+                .direction(theta.sin() * x, theta.sin() * y, theta.cos()),
+        )
+    }
+}
+
+// Everything around the eye, longitude across and latitude up with the target in the middle. Twice
+// as wide as high, as used for environment maps.
+pub struct Equirectangular {
+    view: View,
+}
+
+impl Equirectangular {
+    pub fn new(eye: Vec3, target: Vec3, up_dir: Vec3) -> Self {
+        Self {
+            view: View::new(eye, target, up_dir),
+        }
+    }
+}
+
+impl Camera for Equirectangular {
+    fn get_ray(&self, u: R, v: R) -> Ray {
+        let longitude = (u - 0.5) * 2. * PI;
+        let latitude = (v - 0.5) * PI;
+
+        Ray::new(
+            self.view.origin,
+            self.view.direction(
+                latitude.cos() * longitude.sin(),
+                latitude.sin(),
+                latitude.cos() * longitude.cos(),
+            ),
+        )
+    }
+}
+
+// Faces of a cubemap, in the order graphics APIs number them
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CubeFace {
+    PositiveX,
+    NegativeX,
+    PositiveY,
+    NegativeY,
+    PositiveZ,
+    NegativeZ,
+}
+
+impl CubeFace {
+    pub const ALL: [CubeFace; 6] = [
+        CubeFace::PositiveX,
+        CubeFace::NegativeX,
+        CubeFace::PositiveY,
+        CubeFace::NegativeY,
+        CubeFace::PositiveZ,
+        CubeFace::NegativeZ,
+    ];
+
+    // px for +X, nz for -Z
+    pub fn name(&self) -> &'static str {
+        match self {
+            CubeFace::PositiveX => "px",
+            CubeFace::NegativeX => "nx",
+            CubeFace::PositiveY => "py",
+            CubeFace::NegativeY => "ny",
+            CubeFace::PositiveZ => "pz",
+            CubeFace::NegativeZ => "nz",
+        }
+    }
+
+    // The axis the face looks along, then the image's right and up on it. As in OpenGL and
+    // Direct3D, faces are seen from inside the cube, mirrored compared to a camera looking the same
+    // way.
+    fn axes(&self) -> (Vec3, Vec3, Vec3) {
+        let (x, y, z) = (
+            Vec3::new(1., 0., 0.),
+            Vec3::new(0., 1., 0.),
+            Vec3::new(0., 0., 1.),
+        );
+        match self {
+            CubeFace::PositiveX => (x, -z, y),
+            CubeFace::NegativeX => (-x, z, y),
+            CubeFace::PositiveY => (y, x, -z),
+            CubeFace::NegativeY => (-y, x, z),
+            CubeFace::PositiveZ => (z, x, y),
+            CubeFace::NegativeZ => (-z, -x, y),
+        }
+    }
+}
+
+// One square face of a cubemap around the eye, a 90 degree view along an axis whatever the
+// camera's target
+pub struct CubemapFace {
+    origin: Vec3,
+    forward: Vec3,
+    right: Vec3,
+    up: Vec3,
+}
+
+impl CubemapFace {
+    pub fn new(eye: Vec3, face: CubeFace) -> Self {
+        let (forward, right, up) = face.axes();
+
+        Self {
+            origin: eye,
+            forward,
+            right,
+            up,
+        }
+    }
+}
+
+impl Camera for CubemapFace {
+    fn get_ray(&self, u: R, v: R) -> Ray {
+        Ray::new(
+            self.origin,
+            self.forward + (2. * u - 1.) * self.right + (2. * v - 1.) * self.up,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use raytracer_core::hittable::objects::World;
+    use raytracer_core::lighting::LightingSettings;
+    use raytracer_core::render::{render, RenderOptions, Scene};
+    use std::sync::Mutex;
+
+    fn camera(aperture: R, focus_dist: R) -> ThinLens {
+        ThinLens::new(
+            Vec3::new(0., 0., 0.),
+            Vec3::new(0., 0., -1.),
+            Vec3::new(0., 1., 0.),
+            90.,
+            1.,
+            aperture,
+            focus_dist,
+        )
+    }
+
+    #[test]
+    fn lens_radius_is_half_the_aperture() {
+        let camera = camera(0.5, 10.);
+
+        let widest = (0..1000)
+            .map(|_| camera.get_ray(0.5, 0.5).origin().len())
+            .fold(0., R::max);
+
+        assert!(widest <= 0.25 && widest > 0.2, "{}", widest);
+    }
+
+    #[test]
+    fn pinhole_rays_start_at_the_eye() {
+        let camera = camera(0., 10.);
+
+        for _ in 0..10 {
+            assert_eq!(Vec3::new(0., 0., 0.), camera.get_ray(0.3, 0.8).origin());
+        }
+    }
+
+    #[test]
+    fn rays_through_a_pixel_meet_at_the_focus_distance() {
+        let camera = camera(2., 3.);
+
+        let focus = Vec3::new(3., 3., -3.);
+        for _ in 0..10 {
+            let ray = camera.get_ray(1., 1.);
+            assert!((ray.at(1.) - focus).len() < 1e-5, "{:?}", ray.at(1.));
+        }
+    }
+
+    #[test]
+    fn physical_lens_matches_the_field_of_view() {
+        let lens = PhysicalLens {
+            focal_length_mm: 50.,
+            f_stop: 2.,
+            sensor_width_mm: 36.,
+        };
+        assert!((lens.aperture() - 0.025).abs() < 1e-6);
+
+        let camera = ThinLens::physical(
+            Vec3::new(0., 0., 0.),
+            Vec3::new(0., 0., -1.),
+            Vec3::new(0., 1., 0.),
+            1.5,
+            lens,
+            4.,
+        );
+
+        // The right edge of the image is half the sensor width off the lens axis, focal length away
+        let edge = camera.get_ray(1., 0.5).at(1.);
+        assert!((edge.x / -edge.z - 18. / 50.).abs() < 1e-5, "{:?}", edge);
+        let top = camera.get_ray(0.5, 1.).at(1.);
+        assert!((top.y / -top.z - 12. / 50.).abs() < 1e-5, "{:?}", top);
+    }
+
+    #[test]
+    fn polygon_bokeh_stays_inside_its_blades() {
+        let blades = 6;
+        let bokeh = Bokeh::Polygon {
+            blades,
+            rotation_degrees: 0.,
+        };
+        // Halfway between two corners, the first one being straight up
+        let apothem = (PI / blades as R).cos();
+
+        let mut beyond_apothem = 0;
+        for _ in 0..2000 {
+            let (x, y) = bokeh.sample();
+            for i in 0..blades {
+                let angle = PI / 2. + PI / blades as R + 2. * PI * i as R / blades as R;
+                assert!(x * angle.cos() + y * angle.sin() <= apothem + 1e-5);
+            }
+            if (x * x + y * y).sqrt() > apothem {
+                beyond_apothem += 1;
+            }
+        }
+
+        // A circle would never get into the corners
+        assert!(beyond_apothem > 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 3 blades")]
+    fn polygon_bokeh_needs_three_blades() {
+        ThinLens::new(
+            Vec3::new(0., 0., 0.),
+            Vec3::new(0., 0., -1.),
+            Vec3::new(0., 1., 0.),
+            20.,
+            1.,
+            2.,
+            1.,
+        )
+        .with_bokeh(Bokeh::Polygon {
+            blades: 0,
+            rotation_degrees: 0.,
+        });
+    }
+
+    fn assert_direction(expected: Vec3, ray: &Ray) {
+        let actual = ray.direction().unit_vector();
+        assert!((expected - actual).len() < 1e-5, "{:?}", actual);
+    }
+
+    fn settings(model: CameraModel) -> CameraSettings {
+        CameraSettings {
+            eye: Vec3::new(1., 2., 3.),
+            target: Vec3::new(1., 2., 0.),
+            up_dir: Vec3::new(0., 1., 0.),
+            model,
+        }
+    }
+
+    #[test]
+    fn orthographic_rays_are_parallel_and_start_around_the_eye() {
+        let camera = settings(CameraModel::Orthographic { width: 4. }).into_camera(2.);
+
+        for (u, v, origin) in &[
+            (0.5, 0.5, Vec3::new(1., 2., 3.)),
+            (0., 0., Vec3::new(-1., 1., 3.)),
+            (1., 1., Vec3::new(3., 3., 3.)),
+        ] {
+            let ray = camera.get_ray(*u, *v);
+            assert!((ray.origin() - *origin).len() < 1e-5, "{:?}", ray.origin());
+            assert_eq!(Vec3::new(0., 0., -1.), ray.direction());
+        }
+    }
+
+    #[test]
+    fn fisheye_angle_grows_evenly_from_the_center() {
+        let camera = settings(CameraModel::Fisheye { fov_degrees: 180. }).into_camera(2.);
+
+        assert_direction(Vec3::new(0., 0., -1.), &camera.get_ray(0.5, 0.5));
+        // Half the fov at the top, which is the shorter side
+        assert_direction(Vec3::new(0., 1., 0.), &camera.get_ray(0.5, 1.));
+        assert_direction(Vec3::new(-1., 0., 0.), &camera.get_ray(0.25, 0.5));
+        let diagonal = (0.5 as R).sqrt();
+        assert_direction(
+            Vec3::new(0.5, 0.5, -diagonal),
+            &camera.get_ray(0.5 + 0.25 * diagonal / 2., 0.5 + 0.25 * diagonal),
+        );
+        assert_eq!(Vec3::new(1., 2., 3.), camera.get_ray(0.9, 0.1).origin());
+    }
+
+    #[test]
+    fn equirectangular_sees_all_around() {
+        let camera = settings(CameraModel::Equirectangular).into_camera(2.);
+
+        assert_direction(Vec3::new(0., 0., -1.), &camera.get_ray(0.5, 0.5));
+        assert_direction(Vec3::new(1., 0., 0.), &camera.get_ray(0.75, 0.5));
+        assert_direction(Vec3::new(-1., 0., 0.), &camera.get_ray(0.25, 0.5));
+        assert_direction(Vec3::new(0., 0., 1.), &camera.get_ray(0., 0.5));
+        assert_direction(Vec3::new(0., 0., 1.), &camera.get_ray(1., 0.5));
+        assert_direction(Vec3::new(0., 1., 0.), &camera.get_ray(0.3, 1.));
+        assert_direction(Vec3::new(0., -1., 0.), &camera.get_ray(0.8, 0.));
+    }
+
+    #[test]
+    fn thin_lens_focuses_on_the_target_by_default() {
+        let camera = settings(CameraModel::ThinLens {
+            v_fov_degrees: 90.,
+            aperture: 1.,
+            focus_dist: None,
+            lens: None,
+            bokeh: Bokeh::Circle,
+        })
+        .into_camera(1.);
+
+        for _ in 0..10 {
+            let ray = camera.get_ray(0.5, 0.5);
+            assert!((ray.at(1.) - Vec3::new(1., 2., 0.)).len() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn validate_checks_the_model_and_the_view() {
+        assert!(CameraSettings::default().validate().is_empty());
+
+        let errors = CameraSettings {
+            target: Vec3::new(1., 5., 3.),
+            ..settings(CameraModel::Fisheye { fov_degrees: 400. })
+        }
+        .validate();
+        assert_eq!(2, errors.len(), "{:?}", errors);
+        assert!(errors[0].contains("fov_degrees"));
+        assert!(errors[1].contains("parallel"));
+    }
+
+    #[test]
+    fn thin_lens_checks_its_lens_and_bokeh() {
+        let errors = settings(CameraModel::ThinLens {
+            v_fov_degrees: 20.,
+            aperture: 0.,
+            focus_dist: None,
+            lens: Some(PhysicalLens {
+                focal_length_mm: 50.,
+                f_stop: 0.,
+                sensor_width_mm: 36.,
+            }),
+            bokeh: Bokeh::Polygon {
+                blades: 2,
+                rotation_degrees: 0.,
+            },
+        })
+        .validate();
+
+        assert_eq!(2, errors.len(), "{:?}", errors);
+        assert!(errors[0].contains("f_stop"));
+        assert!(errors[1].contains("blades"));
+    }
+
+    #[test]
+    fn thin_lens_uses_the_physical_lens_when_set() {
+        let lens = PhysicalLens {
+            focal_length_mm: 50.,
+            f_stop: 2.,
+            sensor_width_mm: 36.,
+        };
+        let camera = settings(CameraModel::ThinLens {
+            v_fov_degrees: 90.,
+            aperture: 1.,
+            focus_dist: None,
+            lens: Some(lens),
+            bokeh: Bokeh::Polygon {
+                blades: 5,
+                rotation_degrees: 10.,
+            },
+        })
+        .into_camera(1.5);
+
+        // 36mm wide at 50mm, not the 90 degrees of v_fov_degrees
+        let edge = camera.get_ray(1., 0.5);
+        let direction = edge.at(1.) - edge.origin();
+        assert!((direction.x / -direction.z - 18. / 50.).abs() < 1e-2);
+        // The 25mm opening, rather than the 1 of aperture
+        let widest = (0..1000)
+            .map(|_| (camera.get_ray(0.5, 0.5).origin() - Vec3::new(1., 2., 3.)).len())
+            .fold(0., R::max);
+        assert!(widest <= 0.0125 && widest > 0.01, "{}", widest);
+    }
+
+    #[test]
+    fn cubemap_faces_follow_the_graphics_api_layout() {
+        let eye = Vec3::new(1., 2., 3.);
+        // Center, right edge and top edge of each face
+        let expected = [
+            (
+                Vec3::new(1., 0., 0.),
+                Vec3::new(1., 0., -1.),
+                Vec3::new(1., 1., 0.),
+            ),
+            (
+                Vec3::new(-1., 0., 0.),
+                Vec3::new(-1., 0., 1.),
+                Vec3::new(-1., 1., 0.),
+            ),
+            (
+                Vec3::new(0., 1., 0.),
+                Vec3::new(1., 1., 0.),
+                Vec3::new(0., 1., -1.),
+            ),
+            (
+                Vec3::new(0., -1., 0.),
+                Vec3::new(1., -1., 0.),
+                Vec3::new(0., -1., 1.),
+            ),
+            (
+                Vec3::new(0., 0., 1.),
+                Vec3::new(1., 0., 1.),
+                Vec3::new(0., 1., 1.),
+            ),
+            (
+                Vec3::new(0., 0., -1.),
+                Vec3::new(-1., 0., -1.),
+                Vec3::new(0., 1., -1.),
+            ),
+        ];
+
+        for (face, (center, right, top)) in CubeFace::ALL.iter().zip(expected.iter()) {
+            let camera = CubemapFace::new(eye, *face);
+            assert_eq!(eye, camera.get_ray(0.2, 0.7).origin());
+            assert_eq!(*center, camera.get_ray(0.5, 0.5).direction(), "{:?}", face);
+            assert_eq!(*right, camera.get_ray(1., 0.5).direction(), "{:?}", face);
+            assert_eq!(*top, camera.get_ray(0.5, 1.).direction(), "{:?}", face);
+        }
+    }
+
+    #[test]
+    fn cubemap_corners_are_shared_by_three_faces() {
+        let corner = |face: CubeFace, u: R, v: R| {
+            CubemapFace::new(Vec3::new(0., 0., 0.), face)
+                .get_ray(u, v)
+                .direction()
+        };
+
+        let top_right_front = Vec3::new(1., 1., 1.);
+        assert_eq!(top_right_front, corner(CubeFace::PositiveX, 0., 1.));
+        assert_eq!(top_right_front, corner(CubeFace::PositiveY, 1., 0.));
+        assert_eq!(top_right_front, corner(CubeFace::PositiveZ, 1., 1.));
+    }
+
+    // Keeps the direction of every ray the renderer asks a face for, with where on the face it was
+    struct RecordedFace {
+        face: CubemapFace,
+        rays: Mutex<Vec<(R, R, Vec3)>>,
+    }
+
+    impl RayCaster for RecordedFace {
+        fn ray(&self, u: R, v: R) -> Ray {
+            let ray = self.face.get_ray(u, v);
+            self.rays.lock().unwrap().push((u, v, ray.direction()));
+            ray
+        }
+    }
+
+    // Directions through the face's left or right column of pixels, from the bottom up
+    fn edge_column(face: CubeFace, right: bool) -> Vec<Vec3> {
+        let camera = RecordedFace {
+            face: CubemapFace::new(Vec3::new(0., 0., 0.), face),
+            rays: Mutex::new(vec![]),
+        };
+        let scene = Scene {
+            camera: &camera,
+            world: &World::new(),
+            lighting: &LightingSettings::default(),
+        };
+        render(&scene, &RenderOptions::new(8, 8));
+
+        let mut rays = camera.rays.into_inner().unwrap();
+        let edge = if right {
+            rays.iter().map(|r| r.0).fold(R::MIN, R::max)
+        } else {
+            rays.iter().map(|r| r.0).fold(R::MAX, R::min)
+        };
+        rays.retain(|r| r.0 == edge);
+        rays.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+
+        rays.iter().map(|r| r.2.unit_vector()).collect()
+    }
+
+    #[test]
+    fn adjacent_cubemap_faces_do_not_repeat_the_seam() {
+        // The right edge of +X meets the left edge of -Z
+        let px = edge_column(CubeFace::PositiveX, true);
+        let nz = edge_column(CubeFace::NegativeZ, false);
+
+        assert_eq!(8, px.len());
+        assert_eq!(8, nz.len());
+        for (a, b) in px.iter().zip(nz.iter()) {
+            // Through pixel centers half a pixel on either side of the seam, rather than both on it
+            assert!((*a - *b).len() > 0.05, "{:?} {:?}", a, b);
+            let mirrored = Vec3::new(-b.z, b.y, -b.x);
+            assert!((*a - mirrored).len() < 1e-5, "{:?} {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn stereo_eyes_are_apart_along_the_camera_right() {
+        let settings = settings(CameraModel::Equirectangular);
+
+        let (left, right) = settings.stereo_pair(0.064);
+
+        assert!((left.eye - Vec3::new(0.968, 2., 3.)).len() < 1e-6);
+        assert!((right.eye - Vec3::new(1.032, 2., 3.)).len() < 1e-6);
+        for eye in &[left, right] {
+            let view_dir = eye.target - eye.eye;
+            assert!((view_dir - (settings.target - settings.eye)).len() < 1e-6);
+            assert_eq!(settings.model, eye.model);
+        }
+    }
+}
@@ -1,5 +0,0 @@
-mod hittable;
-pub use hittable::*;
-
-pub mod materials;
-pub mod objects;
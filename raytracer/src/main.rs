@@ -1,53 +1,36 @@
-use image::{ImageBuffer, Rgba, RgbaImage};
-use rayon::prelude::*;
+use raytracer_core::lighting::{LightingSettings, Sky};
+use raytracer_core::render::{render, Progress, RenderOptions, Scene};
+use raytracer_core::{color, hittable, math, time};
+use scene::SceneSettings;
+use std::io::Write;
+use std::path::Path;
 
 pub mod camera;
-pub mod color;
-pub mod hittable;
-pub mod math;
-pub mod ray;
-pub mod time;
+pub mod scene;
 
-use math::{Vec3, INFINITY, PI, R};
+use math::Vec3;
 
 use color::Color;
 use hittable::{
     materials::Material,
     objects::{Sphere, World},
-    Hittable,
 };
-use ray::Ray;
 
 fn main() {
-    // Screen
-    let aspect_ratio = 16.0 / 9.0;
-    let image_width = 480;
-    let image_height: u32 = ((image_width as f32) / aspect_ratio) as u32;
-
-    // Image creation
-    let samples_per_pixel = 100;
-    let mut img: RgbaImage = ImageBuffer::new(image_width, image_height);
-    let max_depth = 50;
-
-    // Camera
-    let camera = {
-        let eye = Vec3::new(3., 3., 2.);
-        let target = Vec3::new(0., 0., -1.);
-        let up_dir = Vec3::new(0., 1., 0.);
-
-        let v_fov_degrees = 20.;
-        let depth_of_field = (eye - target).len();
-        let aperture = 2.;
-
-        camera::Camera::new(
-            eye,
-            target,
-            up_dir,
-            v_fov_degrees,
-            aspect_ratio,
-            aperture,
-            depth_of_field,
-        )
+    // Image size and camera, from the scene file if there is one
+    let settings = match std::env::args().nth(1) {
+        Some(path) => match SceneSettings::load(Path::new(&path)) {
+            Ok(settings) => settings,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        },
+        None => SceneSettings::default(),
+    };
+    let progress = |p: Progress| {
+        eprint!("\rRendering {:3.0}%", p.fraction() * 100.);
+        let _ = std::io::stderr().flush();
     };
 
     // World
@@ -85,103 +68,44 @@ fn main() {
         world
     };
 
+    // No lights, everything is lit by the sky
+    let lighting = LightingSettings {
+        max_depth: 50,
+        lights: vec![],
+        sky: Some(Sky {
+            top: Color::new(0.5, 0.7, 1.0, 1.),
+            bottom: Color::new(1., 1., 1., 1.),
+        }),
+    };
+
+    let shots = settings.shots("test");
+
     // Render loop
     let clock = time::Clock::new();
+    for shot in shots {
+        let options = RenderOptions {
+            samples_per_pixel: 100,
+            progress: Some(&progress),
+            ..RenderOptions::new(shot.width, shot.height)
+        };
 
-    let samples = {
-        let mut s = vec![];
-        for _sample in 0..samples_per_pixel {
-            s.push(_sample);
+        let mut img = image::RgbaImage::new(shot.width * shot.cameras.len() as u32, shot.height);
+        for (i, camera) in shot.cameras.iter().enumerate() {
+            let scene = Scene {
+                camera,
+                world: &world,
+                lighting: &lighting,
+            };
+            let part: image::RgbaImage = render(&scene, &options).into();
+            image::imageops::replace(&mut img, &part, i as u32 * shot.width, 0);
         }
-        s
-    };
+        eprintln!();
 
-    let pixels = {
-        let mut pixels = vec![];
-        for j in 0..image_height {
-            for i in 0..image_width {
-                pixels.push((i, j));
-            }
+        if let Err(e) = img.save(&shot.file_name) {
+            eprintln!("Unable to write '{}': {}", shot.file_name, e);
+            std::process::exit(1);
         }
-        pixels
-    };
-
-    let colors: Vec<(u32, u32, Color)> = pixels
-        .par_iter()
-        .map(|(i, j)| {
-            let i = *i;
-            let j = *j;
-            let mut color = Color {
-                r: 0.,
-                g: 0.,
-                b: 0.,
-                a: 0.,
-            };
-
-            let colors: Vec<Color> = samples
-                //.par_iter() // NOTE: par_iter() doesn't seem to benefit this case.
-                .iter()
-                .enumerate()
-                .map(|(i, _)| {
-                    let (u2, v2) = {
-                        if samples_per_pixel == 1 {
-                            (0., 0.)
-                        } else {
-                            (math::random_normalized(), math::random_normalized())
-                        }
-                    };
-
-                    let u = ((i as R) + u2) / ((image_width - 1) as R);
-                    let v = ((j as R) + v2) / ((image_height - 1) as R);
-
-                    let ray = camera.get_ray(u, v);
-
-                    let c = ray_color(&ray, &world, max_depth);
-                    c
-                })
-                .collect();
-
-            colors.iter().for_each(|c| {
-                color += *c;
-            });
-
-            (i, j, color.from_samples(samples_per_pixel))
-        })
-        .collect();
-
-    // Save img
-    for (i, j, color) in colors {
-        img.put_pixel(i, (image_height - 1) - j, Rgba(color.into()));
+        println!("Wrote {}.", shot.file_name);
     }
-
-    img.save("test.png").unwrap();
     println!("Run time: {:?}.", clock.elapsed());
 }
-
-fn ray_color(ray: &Ray, world: &World, depth: i32) -> Color {
-    let miss_color = Color::new(0., 0., 0., 1.);
-    if depth <= 0 {
-        return miss_color;
-    }
-    let min_hit = 0.001;
-    match world.hit(ray, min_hit, INFINITY) {
-        Some(hr) => match hr.material.scatter(ray, &hr) {
-            Some((attenuation, scattered_dir)) => {
-                let mut col = attenuation * ray_color(&scattered_dir, world, depth - 1);
-                col.a = 1.0;
-                return col;
-            }
-            None => {
-                return miss_color;
-            }
-        },
-        None => {}
-    }
-
-    // Sky
-    let unit_dir = ray.direction().unit_vector();
-    let t = 0.5 * (unit_dir.y + 1.);
-
-    let v_color = (1. - t) * Vec3::new(1., 1., 1.) + t * Vec3::new(0.5, 0.7, 1.0);
-    Color::from_vec3(v_color, 255)
-}
@@ -0,0 +1,320 @@
+use crate::camera::{Camera, CameraSettings, CubeFace, CubemapFace};
+use raytracer_core::math::R;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// What `raytracer <scene.json>` reads, anything left out keeps the settings of the book's image
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SceneSettings {
+    pub image_width: u32,
+    // Equirectangular panoramas should be half as high as they are wide
+    pub image_height: u32,
+    pub camera: CameraSettings,
+    pub output: Output,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub enum Output {
+    // One image through the camera
+    Single,
+    // Six image_width square faces around the camera's eye, for skyboxes and reflection probes
+    Cubemap,
+    // The left eye's image next to the right eye's, each image_width wide
+    Stereo { interocular_distance: R },
+}
+
+// One image file, made of renders placed side by side from left to right
+pub struct Shot {
+    pub file_name: String,
+    pub width: u32,
+    pub height: u32,
+    pub cameras: Vec<Box<dyn Camera>>,
+}
+
+impl Default for SceneSettings {
+    fn default() -> Self {
+        Self {
+            image_width: 480,
+            image_height: 270,
+            camera: CameraSettings::default(),
+            output: Output::Single,
+        }
+    }
+}
+
+impl SceneSettings {
+    pub fn load(path: &Path) -> Result<Self, SceneError> {
+        let contents = fs::read_to_string(path).map_err(|e| SceneError::Io(path.into(), e))?;
+        let settings: Self =
+            serde_json::from_str(&contents).map_err(|e| SceneError::Parse(path.into(), e))?;
+
+        let mut errors = vec![];
+        if settings.image_width == 0 || settings.image_height == 0 {
+            errors.push(format!(
+                "image size must not be zero, got {}x{}",
+                settings.image_width, settings.image_height
+            ));
+        }
+        for e in settings.camera.validate() {
+            errors.push(format!("camera: {}", e));
+        }
+        if let Output::Stereo {
+            interocular_distance,
+        } = settings.output
+        {
+            if interocular_distance <= 0. {
+                errors.push(format!(
+                    "interocular_distance must be positive, got {}",
+                    interocular_distance
+                ));
+            }
+        }
+        if !errors.is_empty() {
+            return Err(SceneError::Invalid(path.into(), errors));
+        }
+
+        Ok(settings)
+    }
+
+    // The images to render, named after `stem`: stem.png, stem_px.png to stem_nz.png for a cubemap
+    pub fn shots(&self, stem: &str) -> Vec<Shot> {
+        let (width, height) = (self.image_width, self.image_height);
+        let aspect_ratio = width as R / height as R;
+
+        match self.output {
+            Output::Single => vec![Shot {
+                file_name: format!("{}.png", stem),
+                width,
+                height,
+                cameras: vec![self.camera.into_camera(aspect_ratio)],
+            }],
+            Output::Cubemap => CubeFace::ALL
+                .iter()
+                .map(|face| Shot {
+                    file_name: format!("{}_{}.png", stem, face.name()),
+                    width,
+                    height: width,
+                    cameras: vec![Box::new(CubemapFace::new(self.camera.eye, *face)) as _],
+                })
+                .collect(),
+            Output::Stereo {
+                interocular_distance,
+            } => {
+                let (left, right) = self.camera.stereo_pair(interocular_distance);
+                vec![Shot {
+                    file_name: format!("{}.png", stem),
+                    width,
+                    height,
+                    cameras: vec![
+                        left.into_camera(aspect_ratio),
+                        right.into_camera(aspect_ratio),
+                    ],
+                }]
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, serde_json::Error),
+    Invalid(PathBuf, Vec<String>),
+}
+
+impl std::fmt::Display for SceneError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SceneError::Io(path, e) => write!(f, "Unable to read '{}': {}", path.display(), e),
+            // serde_json already reports the line and column of the error
+            SceneError::Parse(path, e) => write!(f, "Invalid scene '{}': {}", path.display(), e),
+            SceneError::Invalid(path, errors) => {
+                write!(f, "Invalid scene '{}':", path.display())?;
+                for e in errors {
+                    write!(f, "\n  {}", e)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::{Bokeh, CameraModel, PhysicalLens};
+    use raytracer_core::math::Vec3;
+
+    #[test]
+    fn empty_scene_is_the_default() {
+        let settings: SceneSettings = serde_json::from_str("{}").unwrap();
+
+        assert_eq!(SceneSettings::default(), settings);
+    }
+
+    #[test]
+    fn scene_picks_the_camera_model() {
+        let settings: SceneSettings = serde_json::from_str(
+            r#"{
+                "image_width": 512,
+                "image_height": 256,
+                "camera": {
+                    "eye": { "x": 0, "y": 1, "z": 0 },
+                    "model": "Equirectangular"
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!((512, 256), (settings.image_width, settings.image_height));
+        assert_eq!(CameraModel::Equirectangular, settings.camera.model);
+        assert_eq!(Vec3::new(0., 1., 0.), settings.camera.eye);
+
+        let settings: SceneSettings = serde_json::from_str(
+            r#"{ "camera": { "model": { "Fisheye": { "fov_degrees": 180 } } } }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            CameraModel::Fisheye { fov_degrees: 180. },
+            settings.camera.model
+        );
+    }
+
+    #[test]
+    fn thin_lens_takes_a_lens_and_bokeh() {
+        let settings: SceneSettings = serde_json::from_str(
+            r#"{ "camera": { "model": { "ThinLens": {
+                "focus_dist": 4,
+                "lens": { "focal_length_mm": 85, "f_stop": 1.8, "sensor_width_mm": 36 },
+                "bokeh": { "Polygon": { "blades": 6, "rotation_degrees": 15 } }
+            } } } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            CameraModel::ThinLens {
+                v_fov_degrees: 20.,
+                aperture: 2.,
+                focus_dist: Some(4.),
+                lens: Some(PhysicalLens {
+                    focal_length_mm: 85.,
+                    f_stop: 1.8,
+                    sensor_width_mm: 36.,
+                }),
+                bokeh: Bokeh::Polygon {
+                    blades: 6,
+                    rotation_degrees: 15.,
+                },
+            },
+            settings.camera.model
+        );
+    }
+
+    #[test]
+    fn load_reports_invalid_settings() {
+        let path = std::env::temp_dir().join("raytracer_invalid_scene.json");
+        fs::write(
+            &path,
+            r#"{ "camera": { "model": { "Orthographic": { "width": 0 } } } }"#,
+        )
+        .unwrap();
+
+        let error = SceneSettings::load(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+
+        match &error {
+            SceneError::Invalid(_, errors) => assert_eq!(1, errors.len()),
+            _ => panic!("{}", error),
+        }
+        assert!(error.to_string().contains("camera: orthographic width"));
+    }
+
+    #[test]
+    fn load_reports_unknown_fields() {
+        let path = std::env::temp_dir().join("raytracer_unknown_field_scene.json");
+        fs::write(&path, r#"{ "camera": { "fov": 30 } }"#).unwrap();
+
+        let error = SceneSettings::load(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(error, SceneError::Parse(..)), "{}", error);
+    }
+
+    #[test]
+    fn cubemap_writes_six_square_faces() {
+        let settings: SceneSettings = serde_json::from_str(
+            r#"{ "image_width": 64, "image_height": 32, "output": "Cubemap" }"#,
+        )
+        .unwrap();
+
+        let shots = settings.shots("probe");
+
+        let names: Vec<_> = shots.iter().map(|s| s.file_name.as_str()).collect();
+        assert_eq!(
+            vec![
+                "probe_px.png",
+                "probe_nx.png",
+                "probe_py.png",
+                "probe_ny.png",
+                "probe_pz.png",
+                "probe_nz.png"
+            ],
+            names
+        );
+        for shot in &shots {
+            assert_eq!((64, 64, 1), (shot.width, shot.height, shot.cameras.len()));
+            let ray = shot.cameras[0].get_ray(0.5, 0.5);
+            assert_eq!(settings.camera.eye, ray.origin());
+        }
+    }
+
+    #[test]
+    fn stereo_puts_both_eyes_in_one_image() {
+        let settings: SceneSettings = serde_json::from_str(
+            r#"{
+                "camera": { "model": { "Orthographic": { "width": 2 } } },
+                "output": { "Stereo": { "interocular_distance": 0.5 } }
+            }"#,
+        )
+        .unwrap();
+
+        let shots = settings.shots("test");
+
+        assert_eq!(1, shots.len());
+        assert_eq!("test.png", shots[0].file_name);
+        assert_eq!((480, 270), (shots[0].width, shots[0].height));
+        let centers: Vec<_> = shots[0]
+            .cameras
+            .iter()
+            .map(|c| c.get_ray(0.5, 0.5).origin())
+            .collect();
+        assert_eq!(2, centers.len());
+        assert!(((centers[1] - centers[0]).len() - 0.5).abs() < 1e-5);
+        // The left eye is on the left
+        let right = settings.camera.stereo_pair(1.).1.eye - settings.camera.eye;
+        assert!((centers[1] - centers[0]).dot(right) > 0.);
+    }
+
+    #[test]
+    fn load_rejects_stereo_without_separation() {
+        let path = std::env::temp_dir().join("raytracer_stereo_scene.json");
+        fs::write(
+            &path,
+            r#"{ "output": { "Stereo": { "interocular_distance": 0 } } }"#,
+        )
+        .unwrap();
+
+        let error = SceneSettings::load(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+
+        assert!(
+            error.to_string().contains("interocular_distance"),
+            "{}",
+            error
+        );
+    }
+}
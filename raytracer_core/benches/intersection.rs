@@ -0,0 +1,74 @@
+// Compares the instruction sets on a mesh and on the sphere scene, run with `cargo bench -p raytracer_core`
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use raytracer_core::camera::{AutoFrame, CameraSettings};
+use raytracer_core::hittable::objects::{Mesh, Simd, Spheres};
+use raytracer_core::hittable::Hittable;
+use raytracer_core::math::{Vec3, INFINITY, R};
+use raytracer_core::ray::Ray;
+use raytracer_core::render::RayCaster;
+
+const SIZE: u32 = 64;
+const INSTRUCTION_SETS: [Simd; 3] = [Simd::Scalar, Simd::Sse, Simd::Avx];
+
+fn rays(camera: &dyn RayCaster) -> Vec<Ray> {
+    let mut rays = vec![];
+    for j in 0..SIZE {
+        for i in 0..SIZE {
+            let u = i as R / (SIZE - 1) as R;
+            let v = j as R / (SIZE - 1) as R;
+            rays.push(camera.ray(u, v));
+        }
+    }
+
+    rays
+}
+
+fn trace(hittable: &dyn Hittable, rays: &[Ray]) -> usize {
+    rays.iter()
+        .filter(|ray| hittable.hit(ray, 0.001, INFINITY).is_some())
+        .count()
+}
+
+fn rook(c: &mut Criterion) {
+    let file = concat!(env!("CARGO_MANIFEST_DIR"), "/res/rook.obj");
+    let mesh = Mesh::from_obj(file.into()).unwrap();
+    let camera = CameraSettings::default()
+        .framed(
+            mesh.center(),
+            mesh.bounding_radius(),
+            AutoFrame::default().padding,
+            1.,
+        )
+        .into_camera(1.);
+    let rays = rays(&camera);
+
+    let mut group = c.benchmark_group("rook");
+    for simd in INSTRUCTION_SETS.iter().filter(|simd| simd.is_available()) {
+        let mesh = Mesh::from_obj(file.into()).unwrap().with_simd(*simd);
+        group.bench_function(format!("{:?}", simd), |b| {
+            b.iter(|| trace(black_box(&mesh), &rays))
+        });
+    }
+    group.finish();
+}
+
+fn random_scene(c: &mut Criterion) {
+    let spheres = Spheres::random_scene();
+    // The small spheres are spread over a 22x22 square around the origin
+    let camera = CameraSettings::default()
+        .framed(Vec3::new(0., 0., 0.), 11., 0., 1.)
+        .into_camera(1.);
+    let rays = rays(&camera);
+
+    let mut group = c.benchmark_group("random_scene");
+    for simd in INSTRUCTION_SETS.iter().filter(|simd| simd.is_available()) {
+        let spheres = spheres.clone().with_simd(*simd);
+        group.bench_function(format!("{:?}", simd), |b| {
+            b.iter(|| trace(black_box(&spheres), &rays))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, rook, random_scene);
+criterion_main!(benches);
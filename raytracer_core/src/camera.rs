@@ -0,0 +1,563 @@
+use super::math::{degrees_to_radians, Vec3, R};
+use super::projection::Projection;
+use super::ray::Ray;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
+pub enum Perspective {
+    Perspective { scale: R },
+    // Parallel rays through a view volume this many world units wide, centered on the eye
+    Orthographic { width: R },
+}
+
+#[derive(PartialEq, Debug, Copy, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct CameraSettings {
+    v_fov: R,
+    eye: Vec3,
+    target: Vec3,
+    up_dir: Vec3,
+    perspective: Perspective,
+    focal_len: R,
+    auto_frame: Option<AutoFrame>,
+    projection: Option<Projection>,
+}
+
+// Moves the camera so the mesh fills the image, keeping the direction from target to eye
+#[derive(PartialEq, Debug, Copy, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct AutoFrame {
+    // Empty space around the mesh, as a fraction of half the image size
+    pub padding: R,
+}
+
+impl Default for AutoFrame {
+    fn default() -> Self {
+        Self { padding: 0.1 }
+    }
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            v_fov: 30.,
+            eye: Vec3::new(0., 0., 5.),
+            target: Vec3::new(0., 0., 0.),
+            up_dir: Vec3::new(0., 1., 0.),
+            perspective: Perspective::Perspective { scale: 1. },
+            focal_len: 1.,
+            auto_frame: None,
+            projection: None,
+        }
+    }
+}
+
+impl CameraSettings {
+    // Describes every setting that would produce a degenerate camera
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = vec![];
+
+        match self.perspective {
+            // v_fov and focal_len only shape perspective cameras
+            Perspective::Perspective { scale } => {
+                if self.v_fov <= 0. || self.v_fov >= 180. {
+                    errors.push(format!(
+                        "v_fov must be between 0 and 180 degrees, got {}",
+                        self.v_fov
+                    ));
+                }
+                if self.focal_len <= 0. {
+                    errors.push(format!(
+                        "focal_len must be positive, got {}",
+                        self.focal_len
+                    ));
+                }
+                if scale <= 0. {
+                    errors.push(format!("perspective scale must be positive, got {}", scale));
+                }
+            }
+            Perspective::Orthographic { width } => {
+                if width <= 0. {
+                    errors.push(format!(
+                        "orthographic width must be positive, got {}",
+                        width
+                    ));
+                }
+            }
+        }
+
+        let view_dir = self.target - self.eye;
+        if view_dir.len() == 0. {
+            errors.push("eye and target must be different points".to_string());
+        } else if self.up_dir.len() == 0. {
+            errors.push("up_dir must not be zero".to_string());
+        } else if view_dir
+            .unit_vector()
+            .cross(self.up_dir.unit_vector())
+            .len()
+            <= 1e-4
+        {
+            errors.push("up_dir must not be parallel to the view direction".to_string());
+        }
+
+        if let Some(auto_frame) = self.auto_frame {
+            if auto_frame.padding < 0. || auto_frame.padding >= 1. {
+                errors.push(format!(
+                    "auto_frame padding must be at least 0 and less than 1, got {}",
+                    auto_frame.padding
+                ));
+            }
+        }
+
+        if let Some(projection) = self.projection {
+            for e in projection.validate() {
+                errors.push(format!("projection: {}", e));
+            }
+        }
+
+        errors
+    }
+
+    pub fn auto_frame(&self) -> Option<AutoFrame> {
+        self.auto_frame
+    }
+
+    // Fits a sphere in the image. The sphere looks the same from every direction, so sprites
+    // rendered from different angles all get the same framing.
+    pub fn framed(&self, center: Vec3, radius: R, padding: R, aspect_ratio: R) -> Self {
+        let dir = (self.eye - self.target).unit_vector();
+        let fill = 1. - padding;
+
+        let mut framed = *self;
+        framed.target = center;
+        match &mut framed.perspective {
+            Perspective::Perspective { scale } => {
+                // Half the size of the viewport along its shorter side, at unit distance from the eye
+                let half_size = (degrees_to_radians(self.v_fov) / 2.).tan() * aspect_ratio.min(1.);
+                let half_angle = (fill * *scale * half_size / self.focal_len).atan();
+                framed.eye = center + dir * (radius / half_angle.sin());
+            }
+            Perspective::Orthographic { width } => {
+                // The shorter side is the height for wide images
+                *width = 2. * radius / (fill * (1. / aspect_ratio).min(1.));
+                // Rays start on the plane through the eye, so anywhere outside the sphere works
+                framed.eye = center + dir * (2. * radius);
+            }
+        }
+
+        framed
+    }
+
+    // Replaces the view with the projection preset, if there is one. Takes the output image size,
+    // as tiles are sized in output pixels.
+    pub fn projected(&self, image_width: u32, image_height: u32) -> Self {
+        let projection = match self.projection {
+            Some(projection) => projection,
+            None => return *self,
+        };
+
+        let (dir, up) = projection.view();
+        let pixels_per_unit = projection.pixels_per_unit();
+        let u = up.cross(dir).unit_vector();
+        let v = dir.cross(u);
+
+        // Moves the target so the world origin lands on a pixel corner. Everything a whole number
+        // of pixels away from it then does too, however the target was set.
+        let snap = |offset: R, size: u32| {
+            let half = (size % 2) as R / 2.;
+            ((offset * pixels_per_unit - half).round() + half) / pixels_per_unit - offset
+        };
+        let target = self.target
+            + u * snap(self.target.dot(u), image_width)
+            + v * snap(self.target.dot(v), image_height);
+
+        let mut projected = *self;
+        projected.target = target;
+        projected.eye = target + dir * (self.eye - self.target).len();
+        projected.up_dir = up;
+        projected.perspective = Perspective::Orthographic {
+            width: image_width as R / pixels_per_unit,
+        };
+
+        projected
+    }
+
+    pub fn into_camera(&self, aspect_ratio: R) -> Camera {
+        Camera::new(
+            aspect_ratio,
+            self.v_fov,
+            self.eye,
+            self.target,
+            self.up_dir,
+            self.perspective,
+            self.focal_len,
+        )
+    }
+}
+
+pub struct Camera {
+    lower_left_corner: Vec3,
+    horizontal: Vec3,
+    vertical: Vec3,
+    perspective: Perspective,
+    origin: Vec3,
+    normal: Vec3,
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
+}
+
+impl Camera {
+    fn new(
+        aspect_ratio: R,
+        v_fov: R,
+        eye: Vec3,
+        target: Vec3,
+        up_dir: Vec3,
+        perspective: Perspective,
+        focal_len: R,
+    ) -> Self {
+        let w = (eye - target).unit_vector();
+        let u = up_dir.cross(w).unit_vector();
+        let v = w.cross(u);
+
+        let origin = eye;
+        let (horizontal, vertical, lower_left_corner) = match perspective {
+            // Rays go from the eye through a viewport focal_len in front of it
+            Perspective::Perspective { scale } => {
+                let theta = degrees_to_radians(v_fov);
+                let viewport_height = 2. * (theta / 2.).tan();
+                let viewport_width = aspect_ratio * viewport_height;
+
+                let horizontal = (scale * viewport_width) * u;
+                let vertical = (scale * viewport_height) * v;
+                let lower_left_corner = origin - horizontal / 2. - vertical / 2. - w * focal_len;
+                (horizontal, vertical, lower_left_corner)
+            }
+            // Rays start on the viewport itself, which is centered on the eye
+            Perspective::Orthographic { width } => {
+                let horizontal = width * u;
+                let vertical = (width / aspect_ratio) * v;
+                let lower_left_corner = origin - horizontal / 2. - vertical / 2.;
+                (horizontal, vertical, lower_left_corner)
+            }
+        };
+
+        Self {
+            lower_left_corner,
+            horizontal,
+            vertical,
+            perspective,
+            origin,
+            normal: -w,
+            u,
+            v,
+            w,
+        }
+    }
+
+    pub fn depth(&self, point: Vec3) -> R {
+        // Distance along the view direction rather than to the eye, so flat faces have flat depth
+        (point - self.origin).dot(self.normal)
+    }
+
+    pub fn to_view_space(&self, dir: Vec3) -> Vec3 {
+        // x is right, y is up and z points towards the camera
+        Vec3::new(dir.dot(self.u), dir.dot(self.v), dir.dot(self.w))
+    }
+
+    pub fn ray(&self, u: R, v: R) -> Ray {
+        match self.perspective {
+            Perspective::Perspective { .. } => Ray::new(
+                self.origin,
+                self.lower_left_corner + u * self.horizontal + v * self.vertical - self.origin,
+            ),
+            Perspective::Orthographic { .. } => Ray::new(
+                self.lower_left_corner + u * self.horizontal + v * self.vertical,
+                self.normal,
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::projection::ProjectionPreset;
+
+    fn settings(eye: Vec3, perspective: Perspective) -> CameraSettings {
+        CameraSettings {
+            eye,
+            perspective,
+            ..CameraSettings::default()
+        }
+    }
+
+    fn distance_to_ray(point: Vec3, ray: &Ray) -> R {
+        (point - ray.origin())
+            .cross(ray.direction().unit_vector())
+            .len()
+    }
+
+    #[test]
+    fn framed_perspective_edge_rays_touch_sphere() {
+        let center = Vec3::new(1., 2., 3.);
+        let eye = Vec3::new(5., 0., 9.);
+        let framed =
+            settings(eye, Perspective::Perspective { scale: 1. }).framed(center, 2., 0.2, 1.);
+        let camera = framed.into_camera(1.);
+
+        // Padding of 0.2 leaves 80% of each half of the image for the sphere
+        for (u, v) in &[(0.5, 0.9), (0.5, 0.1), (0.9, 0.5), (0.1, 0.5)] {
+            let d = distance_to_ray(center, &camera.ray(*u, *v));
+            assert!(
+                (d - 2.).abs() < 1e-3,
+                "({}, {}) is {} from the center",
+                u,
+                v,
+                d
+            );
+        }
+        assert!(distance_to_ray(center, &camera.ray(0.5, 0.5)) < 1e-4);
+    }
+
+    #[test]
+    fn framed_keeps_view_direction() {
+        let eye = Vec3::new(5., 0., 9.);
+        let original = settings(eye, Perspective::Perspective { scale: 1. });
+        let framed = original.framed(Vec3::new(1., 2., 3.), 2., 0.1, 1.);
+
+        let before = (original.eye - original.target).unit_vector();
+        let after = (framed.eye - framed.target).unit_vector();
+        assert!((before - after).len() < 1e-5);
+        assert_eq!(Vec3::new(1., 2., 3.), framed.target);
+    }
+
+    #[test]
+    fn framed_fits_shorter_side_of_wide_images() {
+        let center = Vec3::new(0., 0., 0.);
+        let framed = settings(
+            Vec3::new(0., 0., 4.),
+            Perspective::Perspective { scale: 1. },
+        )
+        .framed(center, 1., 0., 2.);
+        let camera = framed.into_camera(2.);
+
+        assert!((distance_to_ray(center, &camera.ray(0.5, 1.)) - 1.).abs() < 1e-3);
+        assert!(distance_to_ray(center, &camera.ray(1., 0.5)) > 1.5);
+    }
+
+    #[test]
+    fn framed_orthographic_fits_sphere_in_shorter_side() {
+        let center = Vec3::new(1., 0., 0.);
+        let framed = settings(
+            Vec3::new(0., 0., 4.),
+            Perspective::Orthographic { width: 1. },
+        )
+        .framed(center, 3., 0.25, 2.);
+        let camera = framed.into_camera(2.);
+
+        // Rays are parallel, so the edge rays are exactly a padded radius from the center
+        let top = camera.ray(0.5, 0.5 + 0.5 * 0.75);
+        assert!((distance_to_ray(center, &top) - 3.).abs() < 1e-4);
+        assert_eq!(top.direction(), camera.ray(0.5, 0.5).direction());
+        assert!((framed.eye - center).len() > 3.);
+    }
+
+    #[test]
+    fn perspective_rays_start_at_eye() {
+        let camera = CameraSettings {
+            v_fov: 90.,
+            eye: Vec3::new(1., 2., 3.),
+            target: Vec3::new(1., 2., 0.),
+            ..CameraSettings::default()
+        }
+        .into_camera(1.);
+
+        for (u, v, dir) in &[
+            (0.5, 0.5, Vec3::new(0., 0., -1.)),
+            (1., 1., Vec3::new(1., 1., -1.)),
+            (0., 0., Vec3::new(-1., -1., -1.)),
+            (1., 0., Vec3::new(1., -1., -1.)),
+        ] {
+            let ray = camera.ray(*u, *v);
+            assert_eq!(Vec3::new(1., 2., 3.), ray.origin());
+            assert!(
+                (ray.direction() - *dir).len() < 1e-5,
+                "{:?}",
+                ray.direction()
+            );
+        }
+    }
+
+    #[test]
+    fn orthographic_rays_start_on_viewport_around_eye() {
+        // Neither the eye nor the target are at the origin, and focal_len is ignored
+        let camera = CameraSettings {
+            eye: Vec3::new(1., 2., 3.),
+            target: Vec3::new(1., 2., 0.),
+            perspective: Perspective::Orthographic { width: 4. },
+            focal_len: 7.,
+            ..CameraSettings::default()
+        }
+        .into_camera(2.);
+
+        for (u, v, origin) in &[
+            (0.5, 0.5, Vec3::new(1., 2., 3.)),
+            (0., 0., Vec3::new(-1., 1., 3.)),
+            (1., 1., Vec3::new(3., 3., 3.)),
+            (1., 0., Vec3::new(3., 1., 3.)),
+        ] {
+            let ray = camera.ray(*u, *v);
+            assert!((ray.origin() - *origin).len() < 1e-5, "{:?}", ray.origin());
+            assert_eq!(Vec3::new(0., 0., -1.), ray.direction());
+        }
+    }
+
+    #[test]
+    fn orthographic_rays_follow_tilted_view() {
+        let camera = CameraSettings {
+            eye: Vec3::new(5., 5., 5.),
+            target: Vec3::new(4., 4., 4.),
+            perspective: Perspective::Orthographic { width: 2. },
+            ..CameraSettings::default()
+        }
+        .into_camera(1.);
+        let dir = Vec3::new(-1., -1., -1.).unit_vector();
+
+        let center = camera.ray(0.5, 0.5);
+        let corner = camera.ray(1., 1.);
+        assert!((center.direction() - dir).len() < 1e-5);
+        assert_eq!(center.direction(), corner.direction());
+        // The corner is half the width right and half the height up of the eye
+        let offset = corner.origin() - Vec3::new(5., 5., 5.);
+        assert!(offset.dot(dir).abs() < 1e-5);
+        assert!((offset.len() - (2. as R).sqrt()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn validate_ignores_fov_of_orthographic_cameras() {
+        let settings = CameraSettings {
+            v_fov: 0.,
+            perspective: Perspective::Orthographic { width: 0. },
+            ..CameraSettings::default()
+        };
+
+        let errors = settings.validate();
+        assert_eq!(1, errors.len());
+        assert!(errors[0].contains("width"));
+    }
+
+    #[test]
+    fn validate_rejects_padding_outside_image() {
+        let settings = CameraSettings {
+            auto_frame: Some(AutoFrame { padding: 1. }),
+            ..CameraSettings::default()
+        };
+
+        assert_eq!(1, settings.validate().len());
+    }
+
+    fn projected(preset: ProjectionPreset, target: Vec3, width: u32, height: u32) -> Camera {
+        let settings = CameraSettings {
+            target,
+            projection: Some(Projection {
+                preset,
+                tile_size: 1.,
+                tile_width: 32,
+                rotation: 0.,
+            }),
+            ..CameraSettings::default()
+        };
+
+        settings
+            .projected(width, height)
+            .into_camera(width as R / height as R)
+    }
+
+    // Pixel coordinates of a point seen through an orthographic camera, y up. Pixel k spans k..k+1
+    // and the renderers sample it at its center.
+    fn pixel(camera: &Camera, p: Vec3, width: u32, height: u32) -> (R, R) {
+        let d = p - camera.lower_left_corner;
+        (
+            d.dot(camera.horizontal) / camera.horizontal.len_squared() * width as R,
+            d.dot(camera.vertical) / camera.vertical.len_squared() * height as R,
+        )
+    }
+
+    fn tile_footprint(camera: &Camera) -> (R, R) {
+        let corners = [
+            Vec3::new(0., 0., 0.),
+            Vec3::new(1., 0., 0.),
+            Vec3::new(1., 0., 1.),
+            Vec3::new(0., 0., 1.),
+        ];
+        let pixels: Vec<(R, R)> = corners.iter().map(|c| pixel(camera, *c, 64, 64)).collect();
+        let extent = |f: fn(&(R, R)) -> R| {
+            let values: Vec<R> = pixels.iter().map(f).collect();
+            values.iter().cloned().fold(-1e9, R::max) - values.iter().cloned().fold(1e9, R::min)
+        };
+
+        (extent(|p| p.0), extent(|p| p.1))
+    }
+
+    #[test]
+    fn dimetric_tiles_are_two_to_one() {
+        let camera = projected(ProjectionPreset::Dimetric, Vec3::default(), 64, 64);
+        let (width, height) = tile_footprint(&camera);
+
+        assert!((width - 32.).abs() < 1e-3, "{}", width);
+        assert!((height - 16.).abs() < 1e-3, "{}", height);
+    }
+
+    #[test]
+    fn isometric_tiles_are_sqrt_3_to_one() {
+        let camera = projected(ProjectionPreset::Isometric, Vec3::default(), 64, 64);
+        let (width, height) = tile_footprint(&camera);
+
+        assert!((width - 32.).abs() < 1e-3, "{}", width);
+        assert!((height - 32. / (3. as R).sqrt()).abs() < 1e-3, "{}", height);
+    }
+
+    #[test]
+    fn top_down_tiles_are_square_with_negative_z_up() {
+        let camera = projected(ProjectionPreset::TopDown, Vec3::default(), 64, 64);
+        let (width, height) = tile_footprint(&camera);
+
+        assert!((width - 32.).abs() < 1e-3 && (height - 32.).abs() < 1e-3);
+        let origin = pixel(&camera, Vec3::new(0., 0., 0.), 64, 64);
+        let north = pixel(&camera, Vec3::new(0., 0., -1.), 64, 64);
+        assert!((north.1 - origin.1 - 32.).abs() < 1e-3);
+    }
+
+    #[test]
+    fn projection_snaps_grid_to_pixel_corners() {
+        // Floor grid points, with an odd height putting the image center in the middle of a pixel
+        let target = Vec3::new(0.37, 0.2, -0.11);
+        let camera = projected(ProjectionPreset::Dimetric, target, 64, 63);
+
+        for p in &[
+            Vec3::new(0., 0., 0.),
+            Vec3::new(1., 0., 0.),
+            Vec3::new(2., 0., -3.),
+        ] {
+            let (x, y) = pixel(&camera, *p, 64, 63);
+            assert!((x - x.round()).abs() < 1e-3, "{:?} is at x {}", p, x);
+            assert!((y - y.round()).abs() < 1e-3, "{:?} is at y {}", p, y);
+        }
+    }
+
+    #[test]
+    fn orthographic_rays_are_parallel_and_start_around_eye() {
+        let target = Vec3::new(3., 2., 1.);
+        let camera = projected(ProjectionPreset::SideView, target, 64, 64);
+
+        let center = camera.ray(0.5, 0.5);
+        let corner = camera.ray(0., 0.);
+        assert_eq!(center.direction(), corner.direction());
+        assert!(distance_to_ray(target, &center) < 1e-4);
+        // 64 pixels at 32 pixels per unit
+        assert!((distance_to_ray(target, &corner) - (2. as R).sqrt()).abs() < 1e-4);
+    }
+}
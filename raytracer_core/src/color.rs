@@ -1,6 +1,9 @@
 use super::math::{clamp, Vec3, R};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Color {
     pub r: R,
     pub g: R,
@@ -69,9 +72,33 @@ impl std::ops::Mul for Color {
     }
 }
 
-impl Into<[u8; 4]> for Color {
-    fn into(self) -> [u8; 4] {
-        [r_u8(self.r), r_u8(self.g), r_u8(self.b), r_u8(self.a)]
+impl std::ops::Mul<R> for Color {
+    type Output = Color;
+    fn mul(self, rhs: R) -> Self {
+        Self {
+            r: self.r * rhs,
+            g: self.g * rhs,
+            b: self.b * rhs,
+            a: self.a * rhs,
+        }
+    }
+}
+
+impl std::ops::Add for Color {
+    type Output = Color;
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            r: self.r + rhs.r,
+            g: self.g + rhs.g,
+            b: self.b + rhs.b,
+            a: self.a + rhs.a,
+        }
+    }
+}
+
+impl From<Color> for [u8; 4] {
+    fn from(color: Color) -> Self {
+        [r_u8(color.r), r_u8(color.g), r_u8(color.b), r_u8(color.a)]
     }
 }
 
@@ -0,0 +1,75 @@
+use crate::math::{Aabb, Vec3, R};
+use crate::ray::Ray;
+
+use super::*;
+use materials::Material;
+
+/// Something a ray can hit.
+///
+/// `t` is the ray parameter everywhere, so the hit is at `ray.at(t)` whatever the length of the
+/// ray's direction, and only hits with `t_min <= t <= t_max` are returned. This is what lets
+/// `World` compare hits from different kinds of objects.
+pub trait Hittable {
+    fn hit(&self, ray: &Ray, t_min: R, t_max: R) -> Option<HitRecord>;
+
+    /// Every stretch of the whole line through the ray that is inside the object, behind the ray's
+    /// origin too, sorted by where they start. They may overlap for objects made of several solids.
+    fn intervals(&self, ray: &Ray) -> Vec<Interval>;
+
+    /// Box around every point the object can be hit at, `None` when there is no such box.
+    fn bounding_box(&self) -> Option<Aabb>;
+}
+
+/// Where a ray enters a solid and where it leaves it again.
+pub struct Interval {
+    pub enter: HitRecord,
+    pub exit: HitRecord,
+}
+
+// Sorts by entry, ties in any order
+pub fn sort_intervals(intervals: &mut [Interval]) {
+    intervals.sort_by(|a, b| a.enter.t.total_cmp(&b.enter.t));
+}
+
+pub struct HitRecord {
+    pub point: Vec3,
+    pub normal: Vec3,
+    pub material: Material,
+    pub material_id: usize,
+    // Ray parameter of the hit, not the distance
+    pub t: R,
+    pub front_face: bool,
+    // Where the hit is on the surface, the barycentric weights of the second and third vertex on
+    // triangles
+    pub uv: (R, R),
+}
+
+impl HitRecord {
+    pub fn new(t: R, point: Vec3, outward_normal: Vec3, ray: &Ray, material: Material) -> Self {
+        let front_face = ray.direction().dot(outward_normal) < 0.;
+        let normal = {
+            if front_face {
+                outward_normal
+            } else {
+                -outward_normal
+            }
+        };
+
+        Self {
+            t,
+            point,
+            normal,
+            front_face,
+            material,
+            material_id: 0,
+            uv: (0., 0.),
+        }
+    }
+
+    // The same surface seen from the other side, for the holes one solid cuts into another. The
+    // normal still faces the ray.
+    pub fn flipped(mut self) -> Self {
+        self.front_face = !self.front_face;
+        self
+    }
+}
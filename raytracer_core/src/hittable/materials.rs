@@ -1,7 +1,7 @@
 use super::*;
-use crate::renderer::color::Color;
-use crate::renderer::math::{random_normalized, Vec3, R};
-use crate::renderer::Ray;
+use crate::color::Color;
+use crate::math::{random_normalized, Vec3, R};
+use crate::ray::Ray;
 
 #[derive(Copy, Clone, Debug)]
 pub enum Material {
@@ -14,8 +14,8 @@ impl Material {
     pub fn color(&self) -> Color {
         match self {
             Material::Lambertian { albedo } => *albedo,
-            Material::Metal { albedo, fuzz } => *albedo,
-            Material::Dielectric { ir } => Color::new(1., 1., 1., 1.),
+            Material::Metal { albedo, .. } => *albedo,
+            Material::Dielectric { .. } => Color::new(1., 1., 1., 1.),
         }
     }
 
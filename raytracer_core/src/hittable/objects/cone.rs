@@ -0,0 +1,171 @@
+use super::surface::{cap, cap_uv, closest, polar, solid_intervals, Frame};
+use super::*;
+use crate::math::quadratic_roots;
+
+// Round cone from the center of its base to its apex. Without the base it's an open funnel, which
+// has no inside for a Csg to use.
+#[derive(Copy, Clone, Debug)]
+pub struct Cone {
+    base: Vec3,
+    apex: Vec3,
+    frame: Frame,
+    height: R,
+    radius: R,
+    cap: bool,
+    material: Material,
+}
+
+impl Cone {
+    pub fn new(base: Vec3, apex: Vec3, radius: R, material: Material) -> Self {
+        Self {
+            base,
+            apex,
+            frame: Frame::new(base, apex - base),
+            height: (apex - base).len(),
+            radius,
+            cap: true,
+            material,
+        }
+    }
+
+    pub fn with_cap(mut self, cap: bool) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    fn crossings(&self, ray: &Ray) -> Vec<HitRecord> {
+        let local = self.frame.local_ray(ray);
+        let (o, d) = (local.origin(), local.direction());
+        let h = self.height;
+        // The radius shrinks by k for every unit up, x² + z² = k² (h - y)²
+        let k2 = (self.radius / h) * (self.radius / h);
+        let above = h - o.y;
+
+        let a = d.x * d.x + d.z * d.z - k2 * d.y * d.y;
+        let b = 2. * (o.x * d.x + o.z * d.z) + 2. * k2 * above * d.y;
+        let c = o.x * o.x + o.z * o.z - k2 * above * above;
+
+        let mut crossings = vec![];
+        for t in quadratic_roots(a, b, c) {
+            let p = local.at(t);
+            // The other half of the double cone is above the apex
+            if p.y < 0. || h < p.y {
+                continue;
+            }
+
+            let normal = Vec3::new(p.x, k2 * (h - p.y), p.z);
+            let normal = if normal.near_zero() {
+                self.frame.axis()
+            } else {
+                self.frame.world_direction(normal.unit_vector())
+            };
+            let mut hr = HitRecord::new(t, ray.at(t), normal, ray, self.material);
+            hr.uv = (polar(p.x, p.z), p.y / h);
+            crossings.push(hr);
+        }
+
+        if self.cap {
+            if let Some((t, p)) = cap(&local, 0., self.radius) {
+                let normal = -self.frame.axis();
+                let mut hr = HitRecord::new(t, ray.at(t), normal, ray, self.material);
+                hr.uv = cap_uv(p, self.radius);
+                crossings.push(hr);
+            }
+        }
+        crossings.sort_by(|a, b| a.t.total_cmp(&b.t));
+
+        crossings
+    }
+}
+
+impl Hittable for Cone {
+    fn hit(&self, ray: &Ray, t_min: R, t_max: R) -> Option<HitRecord> {
+        closest(self.crossings(ray), t_min, t_max)
+    }
+
+    fn intervals(&self, ray: &Ray) -> Vec<Interval> {
+        if !self.cap {
+            return vec![];
+        }
+
+        solid_intervals(self.crossings(ray))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let base = self.frame.circle_bounds(self.base, self.radius);
+        Some(base.surrounding(&Aabb::new(self.apex, self.apex)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::objects::test_util::{assert_near, material, spans};
+    use crate::math::INFINITY;
+
+    // Standing on the origin, as high as it is wide
+    fn cone() -> Cone {
+        Cone::new(Vec3::new(0., 0., 0.), Vec3::new(0., 2., 0.), 1., material())
+    }
+
+    #[test]
+    fn cone_side_hit_narrows_upwards() {
+        let ray = Ray::new(Vec3::new(-5., 1., 0.), Vec3::new(1., 0., 0.));
+
+        let hr = cone().hit(&ray, 0.001, INFINITY).unwrap();
+
+        // Half way up the radius is halved
+        assert_eq!(4.5, hr.t);
+        assert_near(Vec3::new(-2., 1., 0.) / (5. as R).sqrt(), hr.normal, 1e-5);
+        assert_eq!(0.5, hr.uv.1);
+    }
+
+    #[test]
+    fn cone_misses_above_its_apex() {
+        // Would hit the upper half of the double cone
+        let ray = Ray::new(Vec3::new(-5., 3., 0.), Vec3::new(1., 0., 0.));
+
+        assert!(cone().hit(&ray, 0.001, INFINITY).is_none());
+    }
+
+    #[test]
+    fn cone_apex_points_up() {
+        let ray = Ray::new(Vec3::new(0., 5., 0.), Vec3::new(0., -1., 0.));
+
+        let hr = cone().hit(&ray, 0.001, INFINITY).unwrap();
+
+        assert!((hr.t - 3.).abs() < 1e-5);
+        assert_eq!(Vec3::new(0., 1., 0.), hr.normal);
+    }
+
+    #[test]
+    fn capped_cone_is_solid() {
+        let ray = Ray::new(Vec3::new(0.25, -5., 0.), Vec3::new(0., 1., 0.));
+
+        let hr = cone().hit(&ray, 0.001, INFINITY).unwrap();
+        assert_eq!(5., hr.t);
+        assert_eq!(Vec3::new(0., -1., 0.), hr.normal);
+
+        assert_eq!(vec![(5., 6.5)], spans(&cone(), &ray));
+    }
+
+    #[test]
+    fn uncapped_cone_is_open_underneath() {
+        let funnel = cone().with_cap(false);
+        let ray = Ray::new(Vec3::new(0.25, -5., 0.), Vec3::new(0., 1., 0.));
+
+        let hr = funnel.hit(&ray, 0.001, INFINITY).unwrap();
+
+        assert_eq!(6.5, hr.t);
+        assert!(!hr.front_face);
+        assert!(funnel.intervals(&ray).is_empty());
+    }
+
+    #[test]
+    fn cone_box_holds_base_and_apex() {
+        let aabb = cone().bounding_box().unwrap();
+
+        assert_eq!(Vec3::new(-1., 0., -1.), aabb.min);
+        assert_eq!(Vec3::new(1., 2., 1.), aabb.max);
+    }
+}
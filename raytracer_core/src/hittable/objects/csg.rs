@@ -0,0 +1,250 @@
+use super::*;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CsgOp {
+    Union,
+    Intersection,
+    // Everything in a that isn't in b
+    Difference,
+}
+
+impl CsgOp {
+    fn contains(self, in_a: bool, in_b: bool) -> bool {
+        match self {
+            CsgOp::Union => in_a || in_b,
+            CsgOp::Intersection => in_a && in_b,
+            CsgOp::Difference => in_a && !in_b,
+        }
+    }
+}
+
+// Two solids combined by walking along the ray through where it enters and leaves each of them.
+// Both have to be closed, anything without intervals is empty space to a Csg.
+pub struct Csg {
+    op: CsgOp,
+    a: Box<dyn Hittable + Sync>,
+    b: Box<dyn Hittable + Sync>,
+}
+
+impl Csg {
+    pub fn new<A, B>(op: CsgOp, a: A, b: B) -> Self
+    where
+        A: Hittable + Sync + 'static,
+        B: Hittable + Sync + 'static,
+    {
+        Self {
+            op,
+            a: Box::new(a),
+            b: Box::new(b),
+        }
+    }
+}
+
+struct Crossing {
+    hr: HitRecord,
+    from_a: bool,
+    entering: bool,
+}
+
+impl Hittable for Csg {
+    fn hit(&self, ray: &Ray, t_min: R, t_max: R) -> Option<HitRecord> {
+        // The intervals don't overlap, so the first boundary in range is the closest
+        self.intervals(ray)
+            .into_iter()
+            .flat_map(|interval| vec![interval.enter, interval.exit])
+            .find(|hr| t_min <= hr.t && hr.t <= t_max)
+    }
+
+    fn intervals(&self, ray: &Ray) -> Vec<Interval> {
+        let a = self.a.intervals(ray).into_iter().map(|i| (true, i));
+        let b = self.b.intervals(ray).into_iter().map(|i| (false, i));
+
+        let mut crossings = vec![];
+        for (from_a, interval) in a.chain(b) {
+            crossings.push(Crossing {
+                hr: interval.enter,
+                from_a,
+                entering: true,
+            });
+            crossings.push(Crossing {
+                hr: interval.exit,
+                from_a,
+                entering: false,
+            });
+        }
+        crossings.sort_by(|a, b| a.hr.t.total_cmp(&b.hr.t));
+
+        // Depths rather than flags, the intervals of one side can overlap
+        let (mut depth_a, mut depth_b) = (0, 0);
+        let mut inside = false;
+        let mut enter = None;
+        let mut intervals = vec![];
+        for crossing in crossings {
+            let depth = if crossing.from_a {
+                &mut depth_a
+            } else {
+                &mut depth_b
+            };
+            if crossing.entering {
+                *depth += 1;
+            } else {
+                *depth -= 1;
+            }
+
+            if self.op.contains(depth_a > 0, depth_b > 0) == inside {
+                continue;
+            }
+            inside = !inside;
+
+            // Going into b is going out of a difference and the other way round
+            let hr = if !crossing.from_a && self.op == CsgOp::Difference {
+                crossing.hr.flipped()
+            } else {
+                crossing.hr
+            };
+
+            if inside {
+                enter = Some(hr);
+            } else if let Some(enter) = enter.take() {
+                intervals.push(Interval { enter, exit: hr });
+            }
+        }
+
+        intervals
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        match self.op {
+            CsgOp::Union => Some(self.a.bounding_box()?.surrounding(&self.b.bounding_box()?)),
+            // Never bigger than a
+            CsgOp::Intersection | CsgOp::Difference => self.a.bounding_box(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::objects::test_util::{cubes_path, material, spans};
+    use crate::math::INFINITY;
+
+    fn sphere(x: R, radius: R) -> Sphere {
+        Sphere::new(Vec3::new(x, 0., 0.), radius, material())
+    }
+
+    // Along the x axis from far on the left
+    fn ray() -> Ray {
+        Ray::new(Vec3::new(-10., 0., 0.), Vec3::new(1., 0., 0.))
+    }
+
+    #[test]
+    fn sphere_interval_goes_through_the_whole_line() {
+        // Starts inside, so the way in is behind the ray
+        let ray = Ray::new(Vec3::new(0., 0., 0.), Vec3::new(2., 0., 0.));
+
+        assert_eq!(vec![(-0.5, 0.5)], spans(&sphere(0., 1.), &ray));
+    }
+
+    #[test]
+    fn union_merges_overlapping_spheres() {
+        let csg = Csg::new(CsgOp::Union, sphere(0., 2.), sphere(3., 2.));
+
+        assert_eq!(vec![(8., 15.)], spans(&csg, &ray()));
+        assert_eq!(8., csg.hit(&ray(), 0.001, INFINITY).unwrap().t);
+    }
+
+    #[test]
+    fn union_keeps_separate_spheres_apart() {
+        let csg = Csg::new(CsgOp::Union, sphere(0., 1.), sphere(3., 1.));
+
+        assert_eq!(vec![(9., 11.), (12., 14.)], spans(&csg, &ray()));
+        assert_eq!(12., csg.hit(&ray(), 11.5, INFINITY).unwrap().t);
+    }
+
+    #[test]
+    fn intersection_is_the_overlap() {
+        let csg = Csg::new(CsgOp::Intersection, sphere(0., 2.), sphere(3., 2.));
+
+        assert_eq!(vec![(11., 12.)], spans(&csg, &ray()));
+
+        let hr = csg.hit(&ray(), 0.001, INFINITY).unwrap();
+        assert_eq!(11., hr.t);
+        // The way into the lens is through the right sphere
+        assert!(hr.front_face);
+        assert_eq!(Vec3::new(-1., 0., 0.), hr.normal);
+    }
+
+    #[test]
+    fn difference_cuts_a_bite_out() {
+        let csg = Csg::new(CsgOp::Difference, sphere(0., 2.), sphere(-2., 1.));
+
+        assert_eq!(vec![(9., 12.)], spans(&csg, &ray()));
+
+        // The first surface is the inside of the bite, facing the ray on its way in
+        let hr = csg.hit(&ray(), 0.001, INFINITY).unwrap();
+        assert_eq!(9., hr.t);
+        assert!(hr.front_face);
+        assert_eq!(Vec3::new(-1., 0., 0.), hr.normal);
+    }
+
+    #[test]
+    fn difference_can_split_a_solid() {
+        let csg = Csg::new(CsgOp::Difference, sphere(0., 3.), sphere(0., 1.));
+
+        assert_eq!(vec![(7., 9.), (11., 13.)], spans(&csg, &ray()));
+
+        // Leaves the first piece through the hole
+        let hr = csg.hit(&ray(), 8., INFINITY).unwrap();
+        assert_eq!(9., hr.t);
+        assert!(!hr.front_face);
+    }
+
+    #[test]
+    fn difference_that_removes_everything_is_empty() {
+        let csg = Csg::new(CsgOp::Difference, sphere(0., 1.), sphere(0., 2.));
+
+        assert!(spans(&csg, &ray()).is_empty());
+        assert!(csg.hit(&ray(), 0.001, INFINITY).is_none());
+    }
+
+    #[test]
+    fn ray_starting_inside_hits_the_way_out() {
+        let csg = Csg::new(CsgOp::Union, sphere(0., 2.), sphere(3., 2.));
+        let ray = Ray::new(Vec3::new(1., 0., 0.), Vec3::new(1., 0., 0.));
+
+        let hr = csg.hit(&ray, 0.001, INFINITY).unwrap();
+
+        assert_eq!(4., hr.t);
+        assert!(!hr.front_face);
+    }
+
+    #[test]
+    fn csgs_nest() {
+        // A sphere with a bite out of both sides
+        let bitten = Csg::new(CsgOp::Difference, sphere(0., 2.), sphere(-2., 1.));
+        let csg = Csg::new(CsgOp::Difference, bitten, sphere(2., 1.));
+
+        assert_eq!(vec![(9., 11.)], spans(&csg, &ray()));
+    }
+
+    #[test]
+    fn overlapping_intervals_of_one_side_count_as_one_solid() {
+        let mut spheres = Spheres::new();
+        spheres.add(sphere(0., 2.));
+        spheres.add(sphere(1., 2.));
+        let csg = Csg::new(CsgOp::Difference, spheres, sphere(0., 1.));
+
+        assert_eq!(vec![(8., 9.), (11., 13.)], spans(&csg, &ray()));
+    }
+
+    #[test]
+    fn mesh_box_with_a_sphere_cut_out() {
+        let mesh = Mesh::from_obj(cubes_path()).unwrap();
+        let ray = Ray::new(Vec3::new(-10., 0., 0.), Vec3::new(1., 0., 0.));
+        assert_eq!(vec![(9., 11.)], spans(&mesh, &ray));
+
+        let csg = Csg::new(CsgOp::Difference, mesh, sphere(-1., 0.5));
+
+        assert_eq!(vec![(9.5, 11.)], spans(&csg, &ray));
+    }
+}
@@ -0,0 +1,171 @@
+use super::surface::{cap, cap_uv, closest, polar, solid_intervals, Frame};
+use super::*;
+use crate::math::quadratic_roots;
+
+// Round pillar from the center of its base to the center of its top. Without caps it's a tube,
+// which has no inside for a Csg to use.
+#[derive(Copy, Clone, Debug)]
+pub struct Cylinder {
+    base: Vec3,
+    top: Vec3,
+    frame: Frame,
+    height: R,
+    radius: R,
+    caps: bool,
+    material: Material,
+}
+
+impl Cylinder {
+    pub fn new(base: Vec3, top: Vec3, radius: R, material: Material) -> Self {
+        Self {
+            base,
+            top,
+            frame: Frame::new(base, top - base),
+            height: (top - base).len(),
+            radius,
+            caps: true,
+            material,
+        }
+    }
+
+    pub fn with_caps(mut self, caps: bool) -> Self {
+        self.caps = caps;
+        self
+    }
+
+    fn crossings(&self, ray: &Ray) -> Vec<HitRecord> {
+        let local = self.frame.local_ray(ray);
+        let (o, d) = (local.origin(), local.direction());
+        let (h, r) = (self.height, self.radius);
+
+        let a = d.x * d.x + d.z * d.z;
+        let b = 2. * (o.x * d.x + o.z * d.z);
+        let c = o.x * o.x + o.z * o.z - r * r;
+
+        let mut crossings = vec![];
+        for t in quadratic_roots(a, b, c) {
+            let p = local.at(t);
+            if p.y < 0. || h < p.y {
+                continue;
+            }
+
+            let normal = self.frame.world_direction(Vec3::new(p.x / r, 0., p.z / r));
+            let mut hr = HitRecord::new(t, ray.at(t), normal, ray, self.material);
+            hr.uv = (polar(p.x, p.z), p.y / h);
+            crossings.push(hr);
+        }
+
+        if self.caps {
+            for (height, normal) in &[(0., -1.), (h, 1.)] {
+                if let Some((t, p)) = cap(&local, *height, r) {
+                    let normal = self.frame.axis() * *normal;
+                    let mut hr = HitRecord::new(t, ray.at(t), normal, ray, self.material);
+                    hr.uv = cap_uv(p, r);
+                    crossings.push(hr);
+                }
+            }
+        }
+        crossings.sort_by(|a, b| a.t.total_cmp(&b.t));
+
+        crossings
+    }
+}
+
+impl Hittable for Cylinder {
+    fn hit(&self, ray: &Ray, t_min: R, t_max: R) -> Option<HitRecord> {
+        closest(self.crossings(ray), t_min, t_max)
+    }
+
+    fn intervals(&self, ray: &Ray) -> Vec<Interval> {
+        if !self.caps {
+            return vec![];
+        }
+
+        solid_intervals(self.crossings(ray))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let base = self.frame.circle_bounds(self.base, self.radius);
+        let top = self.frame.circle_bounds(self.top, self.radius);
+        Some(base.surrounding(&top))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::objects::test_util::{material, spans};
+    use crate::math::INFINITY;
+
+    // Standing on the origin, 2 high
+    fn cylinder() -> Cylinder {
+        Cylinder::new(Vec3::new(0., 0., 0.), Vec3::new(0., 2., 0.), 1., material())
+    }
+
+    #[test]
+    fn cylinder_side_hit() {
+        let ray = Ray::new(Vec3::new(-5., 0.5, 0.), Vec3::new(1., 0., 0.));
+
+        let hr = cylinder().hit(&ray, 0.001, INFINITY).unwrap();
+
+        assert_eq!(4., hr.t);
+        assert_eq!(Vec3::new(-1., 0., 0.), hr.normal);
+        assert!(hr.front_face);
+        assert_eq!(0.25, hr.uv.1);
+    }
+
+    #[test]
+    fn cylinder_side_uv_goes_around() {
+        let u = |x: R, z: R| {
+            let origin = Vec3::new(x * 5., 1., z * 5.);
+            let ray = Ray::new(origin, -origin + Vec3::new(0., 1., 0.));
+            cylinder().hit(&ray, 0.001, INFINITY).unwrap().uv.0
+        };
+
+        let us = [u(1., 0.), u(0., 1.), u(-1., 0.), u(0., -1.)];
+        for (a, b) in us.iter().zip(us.iter().skip(1)) {
+            assert!((((b - a).rem_euclid(1.)) - 0.25).abs() < 1e-5, "{:?}", us);
+        }
+    }
+
+    #[test]
+    fn capped_cylinder_is_hit_from_above() {
+        let ray = Ray::new(Vec3::new(0.5, 5., 0.), Vec3::new(0., -1., 0.));
+
+        let hr = cylinder().hit(&ray, 0.001, INFINITY).unwrap();
+
+        assert_eq!(3., hr.t);
+        assert_eq!(Vec3::new(0., 1., 0.), hr.normal);
+
+        assert_eq!(vec![(3., 5.)], spans(&cylinder(), &ray));
+    }
+
+    #[test]
+    fn uncapped_cylinder_is_seen_through() {
+        let tube = cylinder().with_caps(false);
+        let ray = Ray::new(Vec3::new(0.5, 5., 0.), Vec3::new(0., -1., 0.));
+        assert!(tube.hit(&ray, 0.001, INFINITY).is_none());
+
+        // Into the tube from above, then out through the far side of its wall
+        let ray = Ray::new(Vec3::new(0., 3., 0.), Vec3::new(1., -2., 0.));
+        let hr = tube.hit(&ray, 0.001, INFINITY).unwrap();
+        assert_eq!(1., hr.t);
+        assert!(!hr.front_face);
+        assert!(tube.intervals(&ray).is_empty());
+    }
+
+    #[test]
+    fn tilted_cylinder_box_holds_its_rims() {
+        let cylinder = Cylinder::new(
+            Vec3::new(1., 0., 0.),
+            Vec3::new(3., 2., 0.),
+            0.5,
+            Material::Dielectric { ir: 1.5 },
+        );
+        let aabb = cylinder.bounding_box().unwrap();
+
+        let rim = (0.5 as R).sqrt() / 2.;
+        assert!((aabb.min - Vec3::new(1. - rim, -rim, -0.5)).len() < 1e-5);
+        assert!((aabb.max - Vec3::new(3. + rim, 2. + rim, 0.5)).len() < 1e-5);
+    }
+}
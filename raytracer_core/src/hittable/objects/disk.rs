@@ -0,0 +1,99 @@
+use super::surface::{cap, cap_uv, Frame};
+use super::*;
+
+// Flat, so it has no inside and can't be part of a Csg
+#[derive(Copy, Clone, Debug)]
+pub struct Disk {
+    center: Vec3,
+    frame: Frame,
+    radius: R,
+    material: Material,
+}
+
+impl Disk {
+    pub fn new(center: Vec3, normal: Vec3, radius: R, material: Material) -> Self {
+        Self {
+            center,
+            frame: Frame::new(center, normal),
+            radius,
+            material,
+        }
+    }
+}
+
+impl Hittable for Disk {
+    fn hit(&self, ray: &Ray, t_min: R, t_max: R) -> Option<HitRecord> {
+        let (t, p) = cap(&self.frame.local_ray(ray), 0., self.radius)?;
+        if t < t_min || t_max < t {
+            return None;
+        }
+
+        let mut hr = HitRecord::new(t, ray.at(t), self.frame.axis(), ray, self.material);
+        hr.uv = cap_uv(p, self.radius);
+
+        Some(hr)
+    }
+
+    fn intervals(&self, _ray: &Ray) -> Vec<Interval> {
+        vec![]
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.frame.circle_bounds(self.center, self.radius))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::objects::test_util::material;
+    use crate::math::INFINITY;
+
+    fn disk() -> Disk {
+        Disk::new(Vec3::new(0., 1., 0.), Vec3::new(0., 0., 1.), 2., material())
+    }
+
+    #[test]
+    fn disk_hit_faces_the_ray() {
+        let ray = Ray::new(Vec3::new(1., 1., -4.), Vec3::new(0., 0., 2.));
+
+        let hr = disk().hit(&ray, 0.001, INFINITY).unwrap();
+
+        assert_eq!(2., hr.t);
+        assert_eq!(Vec3::new(1., 1., 0.), hr.point);
+        assert!(!hr.front_face);
+        assert_eq!(Vec3::new(0., 0., -1.), hr.normal);
+    }
+
+    #[test]
+    fn disk_misses_outside_its_radius_and_range() {
+        let ray = Ray::new(Vec3::new(0., 3.5, 4.), Vec3::new(0., 0., -1.));
+        assert!(disk().hit(&ray, 0.001, INFINITY).is_none());
+
+        let ray = Ray::new(Vec3::new(0., 1., 4.), Vec3::new(0., 0., -1.));
+        assert!(disk().hit(&ray, 0.001, 3.9).is_none());
+        assert!(disk().intervals(&ray).is_empty());
+    }
+
+    #[test]
+    fn disk_uv_spans_its_diameter() {
+        let uvs: Vec<_> = [Vec3::new(0., 1., 1.), Vec3::new(-1.5, 1., 1.)]
+            .iter()
+            .map(|origin| {
+                let ray = Ray::new(*origin, Vec3::new(0., 0., -1.));
+                disk().hit(&ray, 0., INFINITY).unwrap().uv
+            })
+            .collect();
+
+        assert_eq!((0.5, 0.5), uvs[0]);
+        assert_eq!((0.125, 0.5), uvs[1]);
+    }
+
+    #[test]
+    fn disk_bounding_box_is_flat() {
+        let aabb = disk().bounding_box().unwrap();
+
+        assert_eq!(Vec3::new(-2., -1., 0.), aabb.min);
+        assert_eq!(Vec3::new(2., 3., 0.), aabb.max);
+    }
+}
@@ -0,0 +1,712 @@
+use super::surface::solid_intervals;
+use super::wide::{lanes, ShearedRay, Simd, TrianglePack, LANES};
+use super::*;
+use crate::math::INFINITY;
+
+#[derive(Copy, Clone, Debug)]
+struct Triangle {
+    v0: Vec3,
+    v1: Vec3,
+    v2: Vec3,
+    normal: Vec3,
+    material_id: usize,
+}
+
+impl Triangle {
+    pub fn new(v0: Vec3, v1: Vec3, v2: Vec3) -> Self {
+        let edge1 = v1 - v0;
+        let edge2 = v2 - v0;
+        let normal = (edge1.cross(edge2)).unit_vector();
+
+        Self {
+            v0,
+            v1,
+            v2,
+            normal,
+            material_id: 0,
+        }
+    }
+
+    pub fn with_material_id(mut self, material_id: usize) -> Self {
+        self.material_id = material_id;
+        self
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (self.v0 + self.v1 + self.v2) / 3.
+    }
+}
+
+pub struct Mesh {
+    triangles: Vec<Triangle>,
+    // The same triangles, LANES at a time, for finding the closest one
+    packs: Vec<TrianglePack>,
+    simd: Simd,
+    material: Material,
+    center: Vec3,
+    bounds: Aabb,
+}
+
+impl Mesh {
+    pub fn new(v0: Vec3, v1: Vec3, v2: Vec3, material: Material) -> Self {
+        let triangle = Triangle::new(v0, v1, v2);
+        Self::from_triangles(vec![triangle], triangle.center(), material)
+    }
+
+    fn from_triangles(triangles: Vec<Triangle>, center: Vec3, material: Material) -> Self {
+        let vertices: Vec<_> = triangles.iter().map(|t| (t.v0, t.v1, t.v2)).collect();
+
+        Self {
+            packs: TrianglePack::pack(&vertices),
+            simd: Simd::best(),
+            bounds: Aabb::around(&corners(&triangles)),
+            triangles,
+            material,
+            center,
+        }
+    }
+
+    pub fn with_simd(mut self, simd: Simd) -> Self {
+        self.simd = simd.or_scalar();
+        self
+    }
+
+    pub fn center(&self) -> Vec3 {
+        self.center
+    }
+
+    // Smallest sphere around `center` that contains every vertex. Unlike the bounding box it
+    // doesn't change as the mesh rotates around its center.
+    pub fn bounding_radius(&self) -> R {
+        corners(&self.triangles)
+            .iter()
+            .map(|v| (*v - self.center).len())
+            .fold(0., R::max)
+    }
+
+    pub fn from_gltf(_filename: String) -> Self {
+        unimplemented!();
+    }
+
+    pub fn from_obj(filename: String) -> Result<Self, MeshError> {
+        use tobj;
+
+        // TODO: materials
+        let (models, _materials) =
+            tobj::load_obj(&filename, false).map_err(|e| MeshError::Load(filename.clone(), e))?;
+
+        Self::from_models(&models)
+    }
+
+    fn from_models(models: &[tobj::Model]) -> Result<Self, MeshError> {
+        let mut triangles = vec![];
+
+        for model in models.iter() {
+            let mesh = &model.mesh;
+            let material_id = mesh.material_id.unwrap_or(0);
+
+            if mesh.positions.len() % 3 != 0 {
+                return Err(MeshError::MalformedPositions(model.name.clone()));
+            }
+
+            let verts: Vec<Vec3> = mesh
+                .positions
+                .chunks(3)
+                .map(|p| Vec3::new(p[0] as R, p[1] as R, p[2] as R))
+                .collect();
+
+            let vertex = |index: u32| -> Result<Vec3, MeshError> {
+                match verts.get(index as usize) {
+                    Some(v) => Ok(*v),
+                    None => Err(MeshError::IndexOutOfBounds {
+                        model: model.name.clone(),
+                        index,
+                        vertex_count: verts.len(),
+                    }),
+                }
+            };
+
+            // Faces are stored as runs of indices, with `num_face_indices` giving the length of each run.
+            let mut offset = 0;
+            for (face, face_len) in mesh.num_face_indices.iter().enumerate() {
+                let face_len = *face_len as usize;
+                if offset + face_len > mesh.indices.len() {
+                    return Err(MeshError::MalformedFace {
+                        model: model.name.clone(),
+                        face,
+                    });
+                }
+
+                let indices = &mesh.indices[offset..offset + face_len];
+                offset += face_len;
+
+                // Lines and points have no surface to hit
+                if face_len < 3 {
+                    continue;
+                }
+
+                // Triangulate quads and ngons as a fan around the first vertex
+                let v0 = vertex(indices[0])?;
+                for edge in indices[1..].windows(2) {
+                    let triangle = Triangle::new(v0, vertex(edge[0])?, vertex(edge[1])?)
+                        .with_material_id(material_id);
+                    triangles.push(triangle);
+                }
+            }
+
+            if offset != mesh.indices.len() {
+                return Err(MeshError::MalformedFace {
+                    model: model.name.clone(),
+                    face: mesh.num_face_indices.len(),
+                });
+            }
+        }
+
+        if triangles.is_empty() {
+            return Err(MeshError::NoTriangles);
+        }
+
+        // Middle of the bounding box, so the bounding sphere around it is as tight as possible
+        let center = Aabb::around(&corners(&triangles)).center();
+
+        let material = Material::Lambertian {
+            albedo: Color::new(0., 1., 1.0, 1.),
+        };
+
+        Ok(Self::from_triangles(triangles, center, material))
+    }
+
+    fn record(
+        &self,
+        ray: &Ray,
+        triangle: &Triangle,
+        intersection: &TriangleRayIntersection,
+    ) -> HitRecord {
+        let point = ray.at(intersection.t);
+
+        let mut rec = HitRecord::new(intersection.t, point, triangle.normal, ray, self.material);
+        rec.material_id = triangle.material_id;
+        rec.uv = (intersection.barycentric.y, intersection.barycentric.z);
+
+        // Fixes degenerate case on isosphere, but seems wrong
+        if !rec.front_face {
+            rec.normal = -rec.normal;
+        }
+
+        rec
+    }
+}
+
+#[derive(Debug)]
+pub enum MeshError {
+    Load(String, tobj::LoadError),
+    MalformedPositions(String),
+    MalformedFace {
+        model: String,
+        face: usize,
+    },
+    IndexOutOfBounds {
+        model: String,
+        index: u32,
+        vertex_count: usize,
+    },
+    NoTriangles,
+}
+
+impl std::fmt::Display for MeshError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MeshError::Load(file, e) => write!(f, "unable to load '{}': {}", file, e),
+            MeshError::MalformedPositions(model) => write!(
+                f,
+                "model '{}' has a position list that is not a multiple of 3",
+                model
+            ),
+            MeshError::MalformedFace { model, face } => write!(
+                f,
+                "model '{}' face {} references more indices than the mesh has",
+                model, face
+            ),
+            MeshError::IndexOutOfBounds {
+                model,
+                index,
+                vertex_count,
+            } => write!(
+                f,
+                "model '{}' references vertex {} but only has {} vertices",
+                model, index, vertex_count
+            ),
+            MeshError::NoTriangles => write!(f, "mesh contains no triangles"),
+        }
+    }
+}
+
+impl std::error::Error for MeshError {}
+
+impl Hittable for Mesh {
+    fn hit(&self, ray: &Ray, t_min: R, t_max: R) -> Option<HitRecord> {
+        let sheared = ShearedRay::new(ray);
+
+        // Equal t goes to the later triangle, as if they were tested one by one
+        let mut closest: Option<(R, usize)> = None;
+        for (i, pack) in self.packs.iter().enumerate() {
+            let t_max = closest.map_or(t_max, |(closest, _)| closest);
+            let (hit, on_edge) = pack.hit(self.simd, &sheared, t_min, t_max);
+
+            let hit = hit.map(|(t, lane)| (t, i * LANES + lane));
+            let edge_hits = lanes(on_edge).filter_map(|lane| {
+                let index = i * LANES + lane;
+                intersect_ray_triangle(&sheared, &self.triangles[index], t_min, t_max)
+                    .map(|intersection| (intersection.t, index))
+            });
+
+            for (t, index) in hit.into_iter().chain(edge_hits) {
+                if closest.is_none_or(|(closest, closest_index)| {
+                    t < closest || (t == closest && index > closest_index)
+                }) {
+                    closest = Some((t, index));
+                }
+            }
+        }
+
+        let triangle = &self.triangles[closest?.1];
+        let intersection = intersect_ray_triangle(&sheared, triangle, t_min, t_max)?;
+
+        Some(self.record(ray, triangle, &intersection))
+    }
+
+    // Treats the mesh as closed: the ray goes in through front faces and out through back faces
+    fn intervals(&self, ray: &Ray) -> Vec<Interval> {
+        let sheared = ShearedRay::new(ray);
+        let mut hits: Vec<_> = self
+            .triangles
+            .iter()
+            .filter_map(|triangle| {
+                intersect_ray_triangle(&sheared, triangle, -INFINITY, INFINITY)
+                    .map(|intersection| self.record(ray, triangle, &intersection))
+            })
+            .collect();
+        hits.sort_by(|a, b| a.t.total_cmp(&b.t));
+
+        solid_intervals(hits)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.bounds)
+    }
+}
+
+fn corners(triangles: &[Triangle]) -> Vec<Vec3> {
+    triangles.iter().flat_map(|t| [t.v0, t.v1, t.v2]).collect()
+}
+
+struct TriangleRayIntersection {
+    t: R,
+    // Weights of v0, v1 and v2 at the hit
+    barycentric: Vec3,
+}
+
+// Watertight ray/triangle test (Woop et al.), rays through a shared edge or vertex hit exactly one
+// of the triangles around it instead of slipping through the crack or hitting several
+fn intersect_ray_triangle(
+    ray: &ShearedRay,
+    triangle: &Triangle,
+    t_min: R,
+    t_max: R,
+) -> Option<TriangleRayIntersection> {
+    let (ax, ay, az) = ray.shear(triangle.v0);
+    let (bx, by, bz) = ray.shear(triangle.v1);
+    let (cx, cy, cz) = ray.shear(triangle.v2);
+
+    // Edge functions of the edges opposite each vertex, the ray is inside when they all have the
+    // same sign. Rounding can only make them zero, never flip their sign.
+    let mut u = cx * by - cy * bx;
+    let mut v = ax * cy - ay * cx;
+    let mut w = bx * ay - by * ax;
+    if u == 0. || v == 0. || w == 0. {
+        u = exact_edge_function(cx, by, cy, bx);
+        v = exact_edge_function(ax, cy, ay, cx);
+        w = exact_edge_function(bx, ay, by, ax);
+    }
+
+    if (u < 0. || v < 0. || w < 0.) && (u > 0. || v > 0. || w > 0.) {
+        return None;
+    }
+
+    let det = u + v + w;
+    if det == 0. {
+        // Seen edge on
+        return None;
+    }
+
+    // A zero edge function means the ray passes exactly through that edge
+    if (u == 0. && !owns_edge(cx - bx, cy - by, det))
+        || (v == 0. && !owns_edge(ax - cx, ay - cy, det))
+        || (w == 0. && !owns_edge(bx - ax, by - ay, det))
+    {
+        return None;
+    }
+
+    let t = (u * az + v * bz + w * cz) / det;
+    if t < t_min || t > t_max {
+        return None;
+    }
+
+    Some(TriangleRayIntersection {
+        t,
+        barycentric: Vec3::new(u / det, v / det, w / det),
+    })
+}
+
+// Products of f32s are exact in f64, so only a real zero stays zero. With the f64 feature this is
+// the same as the first try.
+#[allow(clippy::unnecessary_cast)]
+fn exact_edge_function(a: R, b: R, c: R, d: R) -> R {
+    (a as f64 * b as f64 - c as f64 * d as f64) as R
+}
+
+// Whether the triangle gets a ray passing exactly through its edge going (dx, dy). Decides as if
+// the ray were nudged a tiny bit sideways, so neighbours, which share the edge in opposite
+// directions, never both get it, and the triangles around a vertex agree on one of them.
+fn owns_edge(dx: R, dy: R, det: R) -> bool {
+    let (dx, dy) = if det > 0. { (dx, dy) } else { (-dx, -dy) };
+    dy > 0. || (dy == 0. && dx < 0.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::objects::test_util::{cubes_path, material};
+    use std::io::BufReader;
+
+    fn models_from_str(obj: &str) -> Vec<tobj::Model> {
+        let mut reader = BufReader::new(obj.as_bytes());
+        let (models, _) =
+            tobj::load_obj_buf(&mut reader, false, |_| Err(tobj::LoadError::OpenFileFailed))
+                .unwrap();
+
+        models
+    }
+
+    fn model(positions: Vec<f32>, indices: Vec<u32>, num_face_indices: Vec<u32>) -> tobj::Model {
+        let mut mesh = tobj::Mesh::empty();
+        mesh.positions = positions;
+        mesh.indices = indices;
+        mesh.num_face_indices = num_face_indices;
+
+        tobj::Model::new(mesh, "test".into())
+    }
+
+    #[test]
+    fn mesh_from_obj_loads_cubes() {
+        let mesh = Mesh::from_obj(cubes_path()).unwrap();
+
+        // 5 cubes, 6 quads each, 2 triangles per quad
+        assert_eq!(60, mesh.triangles.len());
+    }
+
+    #[test]
+    fn mesh_from_obj_missing_file_returns_err() {
+        let result = Mesh::from_obj("does_not_exist.obj".into());
+
+        match result {
+            Err(MeshError::Load(_, tobj::LoadError::OpenFileFailed)) => {}
+            _ => panic!("expected a load error"),
+        }
+    }
+
+    #[test]
+    fn mesh_from_models_uses_indices() {
+        // Vertices are shared between the two triangles, so walking the positions
+        // three at a time would build the wrong triangles.
+        let obj = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3\nf 1 3 4\n";
+        let mesh = Mesh::from_models(&models_from_str(obj)).unwrap();
+
+        assert_eq!(2, mesh.triangles.len());
+
+        let t = mesh.triangles[1];
+        assert_eq!(Vec3::new(0., 0., 0.), t.v0);
+        assert_eq!(Vec3::new(1., 1., 0.), t.v1);
+        assert_eq!(Vec3::new(0., 1., 0.), t.v2);
+    }
+
+    #[test]
+    fn mesh_bounds_are_centered_on_bounding_box() {
+        // Most of the triangles are near the origin, which shouldn't pull the center towards it
+        let obj = "v 0 0 0\nv 0.1 0 0\nv 0 0.1 0\nv 0.1 0.1 0\nv 4 0 0\nv 4 2 0\n\
+                   f 1 2 3\nf 2 4 3\nf 1 5 6\n";
+        let mesh = Mesh::from_models(&models_from_str(obj)).unwrap();
+
+        assert_eq!(Vec3::new(2., 1., 0.), mesh.center());
+        assert!((mesh.bounding_radius() - (5. as R).sqrt()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn mesh_from_obj_cubes_bounding_sphere_contains_all_cubes() {
+        let mesh = Mesh::from_obj(cubes_path()).unwrap();
+        let radius = mesh.bounding_radius();
+
+        for t in &mesh.triangles {
+            assert!((t.v0 - mesh.center()).len() <= radius);
+        }
+        // The small cube on top reaches y 2.09 and the big cube spans -1..1
+        assert!(mesh.center().y > 0.);
+        assert!(radius > 1.5);
+    }
+
+    #[test]
+    fn mesh_from_models_triangulates_quads_and_ngons() {
+        let obj = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv -1 1 0\nf 1 2 3 4\nf 1 2 3 4 5\n";
+        let mesh = Mesh::from_models(&models_from_str(obj)).unwrap();
+
+        assert_eq!(2 + 3, mesh.triangles.len());
+        for t in &mesh.triangles {
+            assert_eq!(Vec3::new(0., 0., 0.), t.v0);
+            assert_eq!(Vec3::new(0., 0., 1.), t.normal);
+        }
+    }
+
+    #[test]
+    fn mesh_from_models_combines_models() {
+        let obj =
+            "o A\nv 0 0 0\nv 1 0 0\nv 1 1 0\nf 1 2 3\no B\nv 0 0 1\nv 1 0 1\nv 1 1 1\nf 4 5 6\n";
+        let models = models_from_str(obj);
+        assert_eq!(2, models.len());
+
+        let mesh = Mesh::from_models(&models).unwrap();
+
+        assert_eq!(2, mesh.triangles.len());
+        assert_eq!(Vec3::new(1., 1., 1.), mesh.triangles[1].v2);
+    }
+
+    #[test]
+    fn mesh_from_models_keeps_material_ids() {
+        let mut a = model(
+            vec![0., 0., 0., 1., 0., 0., 1., 1., 0.],
+            vec![0, 1, 2],
+            vec![3],
+        );
+        let mut b = a.clone();
+        a.mesh.material_id = None;
+        b.mesh.material_id = Some(2);
+
+        let mesh = Mesh::from_models(&[a, b]).unwrap();
+
+        assert_eq!(0, mesh.triangles[0].material_id);
+        assert_eq!(2, mesh.triangles[1].material_id);
+    }
+
+    #[test]
+    fn mesh_from_models_index_out_of_bounds_returns_err() {
+        let models = vec![model(
+            vec![0., 0., 0., 1., 0., 0., 1., 1., 0.],
+            vec![0, 1, 3],
+            vec![3],
+        )];
+
+        match Mesh::from_models(&models) {
+            Err(MeshError::IndexOutOfBounds {
+                index: 3,
+                vertex_count: 3,
+                ..
+            }) => {}
+            _ => panic!("expected an out of bounds error"),
+        }
+    }
+
+    #[test]
+    fn mesh_from_models_truncated_face_returns_err() {
+        let models = vec![model(
+            vec![0., 0., 0., 1., 0., 0., 1., 1., 0.],
+            vec![0, 1],
+            vec![3],
+        )];
+
+        match Mesh::from_models(&models) {
+            Err(MeshError::MalformedFace { face: 0, .. }) => {}
+            _ => panic!("expected a malformed face error"),
+        }
+    }
+
+    #[test]
+    fn mesh_from_models_malformed_positions_returns_err() {
+        let models = vec![model(vec![0., 0., 0., 1.], vec![], vec![])];
+
+        match Mesh::from_models(&models) {
+            Err(MeshError::MalformedPositions(_)) => {}
+            _ => panic!("expected a malformed positions error"),
+        }
+    }
+
+    #[test]
+    fn mesh_from_models_no_faces_returns_err() {
+        let obj = "v 0 0 0\nv 1 0 0\nv 1 1 0\n";
+
+        match Mesh::from_models(&models_from_str(obj)) {
+            Err(MeshError::NoTriangles) => {}
+            _ => panic!("expected a no triangles error"),
+        }
+    }
+
+    // Rays from all around the mesh towards points spread over its bounding box
+    fn rays_around(mesh: &Mesh) -> Vec<Ray> {
+        let radius = mesh.bounding_radius();
+        let mut rays = vec![];
+        for i in 0..12 {
+            let angle = i as R * 0.52;
+            let eye = mesh.center()
+                + Vec3::new(angle.cos(), 0.3 * (i as R - 6.), angle.sin()) * radius * 2.;
+            for x in -8..=8 {
+                for y in -8..=8 {
+                    let target =
+                        mesh.center() + Vec3::new(x as R, y as R, (x - y) as R) * (radius / 8.);
+                    rays.push(Ray::new(eye, target - eye));
+                }
+            }
+        }
+
+        rays
+    }
+
+    // The closest hit found by testing every triangle on its own
+    fn brute_force_hit(mesh: &Mesh, ray: &Ray) -> Option<(R, Vec3, usize)> {
+        let sheared = ShearedRay::new(ray);
+        let mut closest: Option<(R, Vec3, usize)> = None;
+        for triangle in &mesh.triangles {
+            if let Some(intersection) = intersect_ray_triangle(&sheared, triangle, 0., INFINITY) {
+                let t = intersection.t;
+                if closest.is_none_or(|(closest, _, _)| t <= closest) {
+                    closest = Some((t, ray.at(t), triangle.material_id));
+                }
+            }
+        }
+
+        closest
+    }
+
+    #[test]
+    fn every_instruction_set_finds_the_closest_triangle() {
+        let mesh = Mesh::from_obj(cubes_path()).unwrap();
+        let rays = rays_around(&mesh);
+
+        for simd in &[Simd::Scalar, Simd::Sse, Simd::Avx] {
+            if !simd.is_available() {
+                continue;
+            }
+
+            let mesh = Mesh::from_obj(cubes_path()).unwrap().with_simd(*simd);
+            let mut hits = 0;
+            for ray in &rays {
+                let hit = mesh
+                    .hit(ray, 0., INFINITY)
+                    .map(|hr| (hr.t, hr.point, hr.material_id));
+
+                assert_eq!(brute_force_hit(&mesh, ray), hit, "{:?}", simd);
+                hits += hit.is_some() as usize;
+            }
+
+            assert!(hits > rays.len() / 4, "{:?} only hit {} rays", simd, hits);
+        }
+    }
+
+    #[test]
+    fn mesh_hit_respects_t_max() {
+        let mesh = Mesh::new(
+            Vec3::new(-1., -1., 0.),
+            Vec3::new(1., -1., 0.),
+            Vec3::new(0., 1., 0.),
+            material(),
+        );
+        let ray = Ray::new(Vec3::new(0., 0., 5.), Vec3::new(0., 0., -1.));
+
+        assert!(mesh.hit(&ray, 0., 4.9).is_none());
+        assert_eq!(5., mesh.hit(&ray, 0., 5.1).unwrap().t);
+
+        // The range is in ray parameters, not distance
+        let ray = Ray::new(Vec3::new(0., 0., 5.), Vec3::new(0., 0., -2.));
+        assert!(mesh.hit(&ray, 0., 2.4).is_none());
+        assert_eq!(2.5, mesh.hit(&ray, 0., 2.6).unwrap().t);
+    }
+
+    #[test]
+    fn triangle_hit_returns_barycentrics_within_range() {
+        let triangle = Triangle::new(
+            Vec3::new(0., 0., 0.),
+            Vec3::new(4., 0., 0.),
+            Vec3::new(0., 2., 0.),
+        );
+        // Twice as long as a unit direction, so the hit is at t 1.5
+        let ray = ShearedRay::new(&Ray::new(Vec3::new(1., 0.5, 3.), Vec3::new(0., 0., -2.)));
+
+        let hit = intersect_ray_triangle(&ray, &triangle, 0., INFINITY).unwrap();
+
+        assert_eq!(1.5, hit.t);
+        assert_eq!(Vec3::new(0.5, 0.25, 0.25), hit.barycentric);
+        assert!(intersect_ray_triangle(&ray, &triangle, 1.6, INFINITY).is_none());
+        assert!(intersect_ray_triangle(&ray, &triangle, 0., 1.4).is_none());
+    }
+
+    // Triangles with the given vertex, or both vertices of an edge
+    fn triangles_with<'a>(mesh: &'a Mesh, vertices: &[Vec3]) -> Vec<&'a Triangle> {
+        mesh.triangles
+            .iter()
+            .filter(|t| vertices.iter().all(|v| [t.v0, t.v1, t.v2].contains(v)))
+            .collect()
+    }
+
+    // Aims from outside the mesh, along the normals around the target so none of the triangles is
+    // seen edge on, and counts how many of them are hit
+    fn hits_around(triangles: &[&Triangle], target: Vec3) -> usize {
+        let outside = triangles.iter().fold(Vec3::default(), |n, t| n + t.normal);
+        let eye = target + outside.unit_vector() * 3.;
+        let ray = Ray::new(eye, target - eye);
+        let sheared = ShearedRay::new(&ray);
+
+        let hits = triangles
+            .iter()
+            .filter(|t| intersect_ray_triangle(&sheared, t, 0., INFINITY).is_some())
+            .count();
+
+        // The packed test sends these to the scalar one and has to come up with the same hit
+        let around: Vec<_> = triangles.iter().map(|t| **t).collect();
+        let hit = Mesh::from_triangles(around, target, material()).hit(&ray, 0., INFINITY);
+        assert!((hit.unwrap().point - target).len() < 1e-5, "{:?}", target);
+
+        hits
+    }
+
+    #[test]
+    fn rays_through_shared_edges_hit_exactly_one_triangle() {
+        let mesh = Mesh::from_obj(cubes_path()).unwrap();
+
+        let mut edges = 0;
+        for t in &mesh.triangles {
+            for (from, to) in &[(t.v0, t.v1), (t.v1, t.v2), (t.v2, t.v0)] {
+                let triangles = triangles_with(&mesh, &[*from, *to]);
+                assert_eq!(2, triangles.len(), "every edge of a cube has two sides");
+
+                for along in &[0.5, 0.25, 1. / 3.] {
+                    let target = *from + (*to - *from) * *along;
+                    assert_eq!(1, hits_around(&triangles, target), "{:?}", target);
+                }
+                edges += 1;
+            }
+        }
+
+        assert_eq!(60 * 3, edges);
+    }
+
+    #[test]
+    fn rays_through_shared_vertices_hit_exactly_one_triangle() {
+        let mesh = Mesh::from_obj(cubes_path()).unwrap();
+
+        for t in &mesh.triangles {
+            for vertex in &[t.v0, t.v1, t.v2] {
+                let triangles = triangles_with(&mesh, &[*vertex]);
+                assert!(triangles.len() >= 3);
+
+                assert_eq!(1, hits_around(&triangles, *vertex), "{:?}", vertex);
+            }
+        }
+    }
+}
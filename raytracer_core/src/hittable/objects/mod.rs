@@ -0,0 +1,628 @@
+use crate::color::Color;
+use crate::math::{random_normalized, random_range, Aabb, Vec3, R};
+use crate::ray::Ray;
+
+use super::*;
+use materials::Material;
+mod cone;
+mod csg;
+mod cylinder;
+mod disk;
+mod mesh;
+mod sdf;
+mod surface;
+#[cfg(test)]
+mod test_util;
+mod torus;
+mod wide;
+pub use cone::Cone;
+pub use csg::{Csg, CsgOp};
+pub use cylinder::Cylinder;
+pub use disk::Disk;
+pub use mesh::{Mesh, MeshError};
+pub use sdf::{Sdf, SdfShape};
+pub use torus::Torus;
+pub use wide::Simd;
+use wide::{SpherePack, LANES};
+
+pub struct World {
+    items: Vec<Box<dyn Hittable + Sync>>,
+}
+
+impl World {
+    pub fn new() -> Self {
+        Self { items: vec![] }
+    }
+
+    pub fn add_item<T>(&mut self, hittable: T)
+    where
+        T: Hittable + Sync + 'static,
+    {
+        self.items.push(Box::new(hittable));
+    }
+
+    // Final scene of Ray Tracing in One Weekend
+    pub fn random_scene() -> Self {
+        let mut world = Self::new();
+        world.add_item(Spheres::random_scene());
+
+        world
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hittable for World {
+    fn hit(&self, ray: &Ray, t_min: R, t_max: R) -> Option<HitRecord> {
+        let mut temp_record: Option<HitRecord> = None;
+        let mut closest_so_far = t_max;
+        for item in &self.items {
+            if let Some(hr) = item.hit(ray, t_min, closest_so_far) {
+                if closest_so_far > hr.t {
+                    closest_so_far = hr.t;
+                    temp_record = Some(hr);
+                }
+            }
+        }
+
+        temp_record
+    }
+
+    fn intervals(&self, ray: &Ray) -> Vec<Interval> {
+        let mut intervals: Vec<_> = self
+            .items
+            .iter()
+            .flat_map(|item| item.intervals(ray))
+            .collect();
+        sort_intervals(&mut intervals);
+
+        intervals
+    }
+
+    // None when empty or when any item is unbounded
+    fn bounding_box(&self) -> Option<Aabb> {
+        let boxes: Option<Vec<_>> = self.items.iter().map(|item| item.bounding_box()).collect();
+        boxes?.into_iter().reduce(|a, b| a.surrounding(&b))
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Sphere {
+    center: Vec3,
+    radius: R,
+    material: Material,
+}
+
+impl Sphere {
+    pub fn new(center: Vec3, radius: R, material: Material) -> Self {
+        Self {
+            center,
+            radius,
+            material,
+        }
+    }
+}
+
+impl Sphere {
+    // Where the line through the ray goes in and out of the sphere
+    fn roots(&self, ray: &Ray) -> Option<(R, R)> {
+        let oc = ray.origin() - self.center;
+        let a = ray.direction().dot(ray.direction());
+        let half_b = oc.dot(ray.direction());
+        let c = oc.len_squared() - self.radius * self.radius;
+        let discriminant = half_b * half_b - a * c;
+
+        if discriminant < 0. {
+            return None;
+        }
+
+        let sqrtd = discriminant.sqrt();
+        Some(((-half_b - sqrtd) / a, (-half_b + sqrtd) / a))
+    }
+
+    fn record(&self, ray: &Ray, t: R) -> HitRecord {
+        let point = ray.at(t);
+        HitRecord::new(
+            t,
+            point,
+            (point - self.center) / self.radius,
+            ray,
+            self.material,
+        )
+    }
+}
+
+impl Hittable for Sphere {
+    fn hit(&self, ray: &Ray, t_min: R, t_max: R) -> Option<HitRecord> {
+        let (near, far) = self.roots(ray)?;
+
+        // The far root is the way out, which is the hit when the ray starts inside
+        let mut root = near;
+        if root < t_min || t_max < root {
+            root = far;
+            if root < t_min || t_max < root {
+                return None;
+            }
+        }
+
+        Some(self.record(ray, root))
+    }
+
+    fn intervals(&self, ray: &Ray) -> Vec<Interval> {
+        match self.roots(ray) {
+            Some((near, far)) => vec![Interval {
+                enter: self.record(ray, near),
+                exit: self.record(ray, far),
+            }],
+            None => vec![],
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        // A negative radius only turns the normals inside out, for hollow glass
+        let radius = self.radius.abs();
+        let r = Vec3::new(radius, radius, radius);
+        Some(Aabb::new(self.center - r, self.center + r))
+    }
+}
+
+// Spheres intersected LANES at a time, for scenes made of many of them
+#[derive(Clone, Debug)]
+pub struct Spheres {
+    spheres: Vec<Sphere>,
+    packs: Vec<SpherePack>,
+    simd: Simd,
+}
+
+impl Spheres {
+    pub fn new() -> Self {
+        Self {
+            spheres: vec![],
+            packs: vec![],
+            simd: Simd::best(),
+        }
+    }
+
+    pub fn add(&mut self, sphere: Sphere) {
+        self.spheres.push(sphere);
+
+        let start = (self.spheres.len() - 1) / LANES * LANES;
+        let last: Vec<_> = self.spheres[start..]
+            .iter()
+            .map(|s| (s.center, s.radius))
+            .collect();
+        self.packs.truncate(start / LANES);
+        self.packs.extend(SpherePack::pack(&last));
+    }
+
+    pub fn with_simd(mut self, simd: Simd) -> Self {
+        self.simd = simd.or_scalar();
+        self
+    }
+
+    pub fn random_scene() -> Self {
+        let mut spheres = Self::new();
+
+        let ground_material = Material::Lambertian {
+            albedo: Color::new(0.5, 0.5, 0.5, 1.),
+        };
+
+        spheres.add(Sphere::new(
+            Vec3::new(0., -1000., 0.),
+            1000.,
+            ground_material,
+        ));
+
+        // random items
+        {
+            for a in -11..11 {
+                for b in -11..11 {
+                    let a = a as R;
+                    let b = b as R;
+
+                    let choose_mat = random_normalized();
+
+                    let center = Vec3::new(
+                        a + 0.9 * random_normalized(),
+                        0.2,
+                        b + 0.9 * random_normalized(),
+                    );
+                    if (center - Vec3::new(4., 0.2, 0.)).len() > 0.9 {
+                        if choose_mat < 0.8 {
+                            let albedo = Vec3::random() * Vec3::random();
+                            let albedo = Color::new(albedo.x, albedo.y, albedo.z, 1.);
+                            let mat = Material::Lambertian { albedo };
+                            spheres.add(Sphere::new(center, 0.2, mat));
+                        } else if choose_mat < 0.95 {
+                            let albedo = Vec3::random_range(0.5, 1.);
+                            let albedo = Color::new(albedo.x, albedo.y, albedo.z, 1.);
+                            let fuzz = random_range(0.5, 1.);
+                            let mat = Material::Metal { albedo, fuzz };
+                            spheres.add(Sphere::new(center, 0.2, mat));
+                        } else {
+                            let mat = Material::Dielectric { ir: 1.5 };
+                            spheres.add(Sphere::new(center, 0.2, mat));
+                        }
+                    }
+                }
+            }
+        }
+
+        let material1 = Material::Dielectric { ir: 1.5 };
+        spheres.add(Sphere::new(Vec3::new(0., 1., 0.), 1., material1));
+
+        let material2 = Material::Lambertian {
+            albedo: Color::new(0.4, 0.2, 0.1, 1.),
+        };
+        spheres.add(Sphere::new(Vec3::new(-4., 1., 0.), 1., material2));
+
+        let material3 = Material::Metal {
+            albedo: Color::new(0.7, 0.6, 0.5, 1.),
+            fuzz: 0.,
+        };
+        spheres.add(Sphere::new(Vec3::new(4., 1., 0.), 1., material3));
+
+        spheres
+    }
+}
+
+impl Default for Spheres {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hittable for Spheres {
+    fn hit(&self, ray: &Ray, t_min: R, t_max: R) -> Option<HitRecord> {
+        let mut closest: Option<(R, usize)> = None;
+        for (i, pack) in self.packs.iter().enumerate() {
+            let t_max = closest.map_or(t_max, |(closest, _)| closest);
+            if let Some((t, lane)) = pack.hit(self.simd, ray, t_min, t_max) {
+                if closest.is_none_or(|(closest, _)| t <= closest) {
+                    closest = Some((t, i * LANES + lane));
+                }
+            }
+        }
+
+        self.spheres[closest?.1].hit(ray, t_min, t_max)
+    }
+
+    fn intervals(&self, ray: &Ray) -> Vec<Interval> {
+        let mut intervals: Vec<_> = self
+            .spheres
+            .iter()
+            .flat_map(|sphere| sphere.intervals(ray))
+            .collect();
+        sort_intervals(&mut intervals);
+
+        intervals
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.spheres
+            .iter()
+            .filter_map(|sphere| sphere.bounding_box())
+            .reduce(|a, b| a.surrounding(&b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_util::{cubes_path, material, random_vec};
+    use super::*;
+    use crate::math::INFINITY;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn ground() -> Sphere {
+        let albedo = Color::new(0.5, 0.5, 0.5, 1.);
+        Sphere::new(
+            Vec3::new(0., -1000., 0.),
+            1000.,
+            Material::Lambertian { albedo },
+        )
+    }
+
+    // Distance of a hit from random_scene's ground, seen from its camera at a grazing angle
+    fn ground_hit_error() -> R {
+        let ray = Ray::new(Vec3::new(13., 2., 3.), Vec3::new(-13., -2.03, -3.));
+        let hr = ground().hit(&ray, 0.001, INFINITY).unwrap();
+
+        ((hr.point - Vec3::new(0., -1000., 0.)).len() - 1000.).abs()
+    }
+
+    #[test]
+    fn sphere_hit_is_on_surface_within_precision() {
+        assert!(ground_hit_error() < 1000. * R::EPSILON * 16.);
+    }
+
+    #[cfg(feature = "f64")]
+    #[test]
+    fn f64_hits_large_spheres_without_acne() {
+        // f32 puts this hit 6e-5 off the surface
+        assert!(ground_hit_error() < 1e-9);
+    }
+
+    #[test]
+    fn every_instruction_set_finds_the_closest_sphere() {
+        let spheres = Spheres::random_scene();
+        assert!(spheres.spheres.len() > LANES * 8);
+
+        for simd in &[Simd::Scalar, Simd::Sse, Simd::Avx] {
+            if !simd.is_available() {
+                continue;
+            }
+
+            let spheres = spheres.clone().with_simd(*simd);
+            for i in 0..400 {
+                let ray = Ray::new(
+                    Vec3::new(13., 2., 3.),
+                    Vec3::new(-13., -2. + (i / 20) as R * 0.2, -3. + (i % 20) as R * 0.4),
+                );
+
+                let expected = spheres
+                    .spheres
+                    .iter()
+                    .filter_map(|s| s.hit(&ray, 0.001, INFINITY))
+                    .map(|hr| hr.t)
+                    .fold(None, |closest: Option<R>, t| {
+                        Some(closest.map_or(t, |c| c.min(t)))
+                    });
+                let actual = spheres.hit(&ray, 0.001, INFINITY).map(|hr| hr.t);
+
+                assert_eq!(expected, actual, "{:?}", simd);
+            }
+        }
+    }
+
+    #[test]
+    fn spheres_hit_respects_range() {
+        let mut spheres = Spheres::new();
+        for z in 0..10 {
+            spheres.add(Sphere::new(
+                Vec3::new(0., 0., -(z as R) * 3.),
+                1.,
+                Material::Dielectric { ir: 1.5 },
+            ));
+        }
+        let ray = Ray::new(Vec3::new(0., 0., 5.), Vec3::new(0., 0., -1.));
+
+        assert_eq!(4., spheres.hit(&ray, 0., INFINITY).unwrap().t);
+        // Starts inside the first sphere
+        assert_eq!(6., spheres.hit(&ray, 4.5, INFINITY).unwrap().t);
+        assert_eq!(7., spheres.hit(&ray, 6.5, INFINITY).unwrap().t);
+        assert!(spheres.hit(&ray, 0., 3.9).is_none());
+    }
+
+    #[test]
+    fn sphere_hit_from_inside_finds_the_way_out() {
+        let sphere = Sphere::new(Vec3::new(0., 0., 0.), 2., Material::Dielectric { ir: 1.5 });
+        let ray = Ray::new(Vec3::new(0., 0., 0.), Vec3::new(0., 0., -1.));
+
+        let hr = sphere.hit(&ray, 0.001, INFINITY).unwrap();
+
+        assert_eq!(2., hr.t);
+        assert!(!hr.front_face);
+        assert_eq!(Vec3::new(0., 0., 1.), hr.normal);
+    }
+
+    // The property tests draw from a fixed seed, so a failing case number stays reproducible
+    const SEED: u64 = 44;
+
+    // Ray from somewhere around the origin towards somewhere else around it, with a direction that
+    // is anything but unit length
+    fn random_ray(rng: &mut StdRng) -> Ray {
+        let origin = random_vec(rng, -6., 6.);
+        let target = random_vec(rng, -2., 2.);
+        Ray::new(origin, (target - origin) * rng.gen_range(0.05, 20.))
+    }
+
+    fn mixed_world() -> Vec<Box<dyn Hittable + Sync>> {
+        let material = material();
+        let mut spheres = Spheres::new();
+        spheres.add(Sphere::new(Vec3::new(1., 0., 0.), 1., material));
+        spheres.add(Sphere::new(Vec3::new(-1., 1., 0.5), 0.7, material));
+
+        vec![
+            Box::new(Sphere::new(Vec3::new(0., -1., -1.), 1.5, material)),
+            Box::new(spheres),
+            Box::new(Mesh::new(
+                Vec3::new(-3., -1., 0.),
+                Vec3::new(3., -1., 0.),
+                Vec3::new(0., 3., 0.2),
+                material,
+            )),
+            Box::new(Mesh::from_obj(cubes_path()).unwrap()),
+            Box::new(Cylinder::new(
+                Vec3::new(-2., -2., 1.),
+                Vec3::new(-1., 1., 2.),
+                0.5,
+                material,
+            )),
+            Box::new(
+                Cone::new(
+                    Vec3::new(2., 1., -1.),
+                    Vec3::new(1., 2.5, 0.),
+                    0.8,
+                    material,
+                )
+                .with_cap(false),
+            ),
+            Box::new(Disk::new(
+                Vec3::new(0., 0., 1.5),
+                Vec3::new(1., 1., 1.),
+                1.,
+                material,
+            )),
+            Box::new(Torus::new(
+                Vec3::new(0.5, -0.5, -1.),
+                Vec3::new(0.3, 1., 0.2),
+                1.2,
+                0.3,
+                material,
+            )),
+            Box::new(SdfShape::new(
+                Sdf::sphere(Vec3::new(-2., 2., -2.), 0.6)
+                    .smooth_union(Sdf::torus(Vec3::new(-2., 1.5, -2.), 1., 0.2), 0.3)
+                    .displace(0.05, 6.),
+                material,
+            )),
+        ]
+    }
+
+    #[test]
+    fn every_hittable_returns_the_ray_parameter_within_range() {
+        let items = mixed_world();
+        let mut rng = StdRng::seed_from_u64(SEED);
+
+        let mut hits = 0;
+        for case in 0..2000 {
+            let ray = random_ray(&mut rng);
+            let (t_min, t_max) = (rng.gen_range(0., 0.5), rng.gen_range(0.5, 2.));
+
+            for item in &items {
+                if let Some(hr) = item.hit(&ray, t_min, t_max) {
+                    assert!(t_min <= hr.t && hr.t <= t_max, "case {}: t {}", case, hr.t);
+                    let error = (ray.at(hr.t) - hr.point).len();
+                    assert!(
+                        error < 1e-4,
+                        "case {}: {:?} is {} from the point",
+                        case,
+                        ray,
+                        error
+                    );
+                    hits += 1;
+                }
+            }
+        }
+
+        assert!(hits > 500, "only {} hits", hits);
+    }
+
+    #[test]
+    fn every_hit_is_inside_the_bounding_box() {
+        let items = mixed_world();
+        let slack = Vec3::new(1e-3, 1e-3, 1e-3);
+        let mut rng = StdRng::seed_from_u64(SEED);
+
+        for case in 0..2000 {
+            let ray = random_ray(&mut rng);
+
+            for item in &items {
+                let aabb = item.bounding_box().unwrap();
+                let aabb = Aabb::new(aabb.min - slack, aabb.max + slack);
+                if let Some(hr) = item.hit(&ray, 0.001, INFINITY) {
+                    assert!(
+                        aabb.contains(hr.point),
+                        "case {}: {:?} outside {:?}",
+                        case,
+                        hr.point,
+                        aabb
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn world_box_surrounds_its_items() {
+        let mut world = World::new();
+        assert!(world.bounding_box().is_none());
+
+        let material = Material::Dielectric { ir: 1.5 };
+        world.add_item(Sphere::new(Vec3::new(0., 0., 0.), 1., material));
+        world.add_item(Sphere::new(Vec3::new(3., 1., 0.), 0.5, material));
+
+        assert_eq!(
+            Some(Aabb::new(Vec3::new(-1., -1., -1.), Vec3::new(3.5, 1.5, 1.))),
+            world.bounding_box()
+        );
+    }
+
+    #[test]
+    fn hollow_sphere_box_is_as_big_as_the_solid_one() {
+        let material = Material::Dielectric { ir: 1.5 };
+        let solid = Sphere::new(Vec3::new(-1., 0., -1.), 0.4, material);
+        let hollow = Sphere::new(Vec3::new(-1., 0., -1.), -0.4, material);
+
+        assert_eq!(solid.bounding_box(), hollow.bounding_box());
+    }
+
+    #[test]
+    fn scaling_the_direction_scales_t() {
+        let items = mixed_world();
+        let mut rng = StdRng::seed_from_u64(SEED);
+
+        for case in 0..2000 {
+            let ray = random_ray(&mut rng);
+            let scale = rng.gen_range(0.1, 10.);
+            let scaled = Ray::new(ray.origin(), ray.direction() * scale);
+
+            for item in &items {
+                let hit = item.hit(&ray, 0.001, INFINITY);
+                let scaled_hit = item.hit(&scaled, 0.001 / scale, INFINITY);
+                match (hit, scaled_hit) {
+                    (Some(hr), Some(scaled_hr)) => {
+                        let error = (hr.point - scaled_hr.point).len();
+                        assert!(
+                            error < 1e-3,
+                            "case {}: {:?} by {} moved the hit {}",
+                            case,
+                            ray,
+                            scale,
+                            error
+                        );
+                        assert!(
+                            (hr.t - scaled_hr.t * scale).abs() < 1e-3 * hr.t.max(1.),
+                            "case {}: t {} and {} scaled by {}",
+                            case,
+                            hr.t,
+                            scaled_hr.t,
+                            scale
+                        );
+                    }
+                    // Grazing hits can fall either way once the direction is rounded
+                    (Some(hr), None) | (None, Some(hr)) => {
+                        let cos = hr.normal.dot(ray.direction().unit_vector());
+                        assert!(cos.abs() < 1e-2, "case {}: only one hit at {}", case, cos)
+                    }
+                    (None, None) => {}
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn world_picks_the_closest_of_mixed_objects() {
+        let mut world = World::new();
+        for item in mixed_world() {
+            world.items.push(item);
+        }
+
+        let mut rng = StdRng::seed_from_u64(SEED);
+
+        for case in 0..2000 {
+            let ray = random_ray(&mut rng);
+
+            let expected = world
+                .items
+                .iter()
+                .filter_map(|item| item.hit(&ray, 0.001, INFINITY))
+                .map(|hr| hr.t)
+                .fold(None, |closest: Option<R>, t| {
+                    Some(closest.map_or(t, |c| c.min(t)))
+                });
+
+            let actual = world.hit(&ray, 0.001, INFINITY).map(|hr| hr.t);
+            assert_eq!(expected, actual, "case {}", case);
+        }
+    }
+}
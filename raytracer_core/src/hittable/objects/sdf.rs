@@ -0,0 +1,462 @@
+use super::surface::{closest, solid_intervals};
+use super::*;
+use crate::math::{clamp, INFINITY};
+
+// Marching gives up after this many steps, rays that pass close to the surface take the most
+const MAX_STEPS: usize = 256;
+// Close enough to the surface to count as a hit
+const EPSILON: R = 1e-4;
+// How far shapes without bounds are searched, as a distance rather than a ray parameter
+const MAX_DISTANCE: R = 100.;
+const NORMAL_STEP: R = 1e-3;
+
+// Distance from a point to the closest surface, negative inside. Shapes that only roughly give the
+// distance, like displaced ones, are marched in smaller steps.
+#[derive(Clone, Debug)]
+pub enum Sdf {
+    Sphere {
+        center: Vec3,
+        radius: R,
+    },
+    // Box with its edges rounded off by `rounding`, which is taken off the inside of the half size
+    RoundBox {
+        center: Vec3,
+        half_size: Vec3,
+        rounding: R,
+    },
+    // Lying flat around the y axis
+    Torus {
+        center: Vec3,
+        major: R,
+        minor: R,
+    },
+    // Blends the two shapes within k of where they meet, a plain union when k is 0
+    SmoothUnion {
+        a: Box<Sdf>,
+        b: Box<Sdf>,
+        k: R,
+    },
+    // Copies of the shape every period along each axis, forever. A period of 0 doesn't repeat
+    // along that axis. The shape has to fit within half a period of the origin.
+    Repeat {
+        sdf: Box<Sdf>,
+        period: Vec3,
+    },
+    // Bumps up to amplitude high, frequency of them every 2π
+    Displace {
+        sdf: Box<Sdf>,
+        amplitude: R,
+        frequency: R,
+    },
+}
+
+impl Sdf {
+    pub fn sphere(center: Vec3, radius: R) -> Self {
+        Sdf::Sphere { center, radius }
+    }
+
+    pub fn round_box(center: Vec3, half_size: Vec3, rounding: R) -> Self {
+        Sdf::RoundBox {
+            center,
+            half_size,
+            rounding,
+        }
+    }
+
+    pub fn torus(center: Vec3, major: R, minor: R) -> Self {
+        Sdf::Torus {
+            center,
+            major,
+            minor,
+        }
+    }
+
+    pub fn smooth_union(self, other: Sdf, k: R) -> Self {
+        Sdf::SmoothUnion {
+            a: Box::new(self),
+            b: Box::new(other),
+            k,
+        }
+    }
+
+    pub fn repeat(self, period: Vec3) -> Self {
+        Sdf::Repeat {
+            sdf: Box::new(self),
+            period,
+        }
+    }
+
+    pub fn displace(self, amplitude: R, frequency: R) -> Self {
+        Sdf::Displace {
+            sdf: Box::new(self),
+            amplitude,
+            frequency,
+        }
+    }
+
+    pub fn distance(&self, p: Vec3) -> R {
+        match self {
+            Sdf::Sphere { center, radius } => (p - *center).len() - radius,
+            Sdf::RoundBox {
+                center,
+                half_size,
+                rounding,
+            } => {
+                let d = p - *center;
+                let q = |axis: usize| d[axis].abs() - (half_size[axis] - rounding);
+                let (x, y, z) = (q(0), q(1), q(2));
+                let outside = Vec3::new(x.max(0.), y.max(0.), z.max(0.)).len();
+                let inside = x.max(y).max(z).min(0.);
+                outside + inside - rounding
+            }
+            Sdf::Torus {
+                center,
+                major,
+                minor,
+            } => {
+                let d = p - *center;
+                let ring = (d.x * d.x + d.z * d.z).sqrt() - major;
+                (ring * ring + d.y * d.y).sqrt() - minor
+            }
+            Sdf::SmoothUnion { a, b, k } => {
+                let (a, b) = (a.distance(p), b.distance(p));
+                if *k <= 0. {
+                    return a.min(b);
+                }
+
+                // Polynomial smooth minimum, at most k / 4 below the closer one
+                let h = clamp(0.5 + 0.5 * (b - a) / k, 0., 1.);
+                b + (a - b) * h - k * h * (1. - h)
+            }
+            Sdf::Repeat { sdf, period } => {
+                let wrap = |axis: usize| {
+                    let period = period[axis];
+                    if period > 0. {
+                        p[axis] - period * (p[axis] / period).round()
+                    } else {
+                        p[axis]
+                    }
+                };
+                sdf.distance(Vec3::new(wrap(0), wrap(1), wrap(2)))
+            }
+            Sdf::Displace {
+                sdf,
+                amplitude,
+                frequency,
+            } => {
+                let bumps =
+                    (frequency * p.x).sin() * (frequency * p.y).sin() * (frequency * p.z).sin();
+                sdf.distance(p) + amplitude * bumps
+            }
+        }
+    }
+
+    // Direction the distance grows fastest, by central differences
+    pub fn normal(&self, p: Vec3) -> Vec3 {
+        let slope = |step: Vec3| self.distance(p + step) - self.distance(p - step);
+        Vec3::new(
+            slope(Vec3::new(NORMAL_STEP, 0., 0.)),
+            slope(Vec3::new(0., NORMAL_STEP, 0.)),
+            slope(Vec3::new(0., 0., NORMAL_STEP)),
+        )
+        .unit_vector()
+    }
+
+    // Box around the surface, None for repeated shapes
+    pub fn bounds(&self) -> Option<Aabb> {
+        let around = |center: Vec3, extent: Vec3| Some(Aabb::new(center - extent, center + extent));
+        match self {
+            Sdf::Sphere { center, radius } => around(*center, Vec3::new(*radius, *radius, *radius)),
+            Sdf::RoundBox {
+                center, half_size, ..
+            } => around(*center, *half_size),
+            Sdf::Torus {
+                center,
+                major,
+                minor,
+            } => around(*center, Vec3::new(major + minor, *minor, major + minor)),
+            Sdf::SmoothUnion { a, b, k } => {
+                let blend = k.max(0.) / 4.;
+                let both = a.bounds()?.surrounding(&b.bounds()?);
+                around(
+                    both.center(),
+                    (both.max - both.min) / 2. + Vec3::new(blend, blend, blend),
+                )
+            }
+            Sdf::Repeat { sdf, period } => {
+                if period.x > 0. || period.y > 0. || period.z > 0. {
+                    None
+                } else {
+                    sdf.bounds()
+                }
+            }
+            Sdf::Displace { sdf, amplitude, .. } => {
+                let inner = sdf.bounds()?;
+                let a = amplitude.abs();
+                around(
+                    inner.center(),
+                    (inner.max - inner.min) / 2. + Vec3::new(a, a, a),
+                )
+            }
+        }
+    }
+
+    // How much faster than the distance to the surface the value can change. Marching steps are
+    // divided by it so they never step over the surface.
+    fn lipschitz(&self) -> R {
+        match self {
+            Sdf::SmoothUnion { a, b, .. } => a.lipschitz().max(b.lipschitz()),
+            Sdf::Repeat { sdf, .. } => sdf.lipschitz(),
+            Sdf::Displace {
+                sdf,
+                amplitude,
+                frequency,
+            } => sdf.lipschitz() + (amplitude * frequency).abs() * (3. as R).sqrt(),
+            _ => 1.,
+        }
+    }
+}
+
+// A shape found by sphere tracing its distance function: stepping along the ray by the distance
+// to the closest surface until it's close enough.
+#[derive(Clone, Debug)]
+pub struct SdfShape {
+    sdf: Sdf,
+    bounds: Option<Aabb>,
+    material: Material,
+}
+
+impl SdfShape {
+    pub fn new(sdf: Sdf, material: Material) -> Self {
+        Self {
+            bounds: sdf.bounds(),
+            sdf,
+            material,
+        }
+    }
+
+    // Only looks for the surface inside the box, which repeated shapes need to be finite
+    pub fn with_bounds(mut self, bounds: Aabb) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    // Distances along the unit direction worth marching, out of start..end
+    fn range(&self, origin: Vec3, direction: Vec3, start: R, end: R) -> Option<(R, R)> {
+        let (near, far) = match self.bounds {
+            Some(bounds) => bounds.slab(origin, direction)?,
+            None => (-MAX_DISTANCE, MAX_DISTANCE),
+        };
+        let (start, end) = (start.max(near - EPSILON), end.min(far + EPSILON));
+
+        if start <= end {
+            Some((start, end))
+        } else {
+            None
+        }
+    }
+
+    // Distance along the unit direction to the first surface after start
+    fn march(&self, origin: Vec3, direction: Vec3, start: R, end: R) -> Option<R> {
+        let scale = 1. / self.sdf.lipschitz();
+
+        let mut s = start;
+        for _ in 0..MAX_STEPS {
+            // Inside, the distance is to the way out
+            let distance = self.sdf.distance(origin + direction * s).abs();
+            if distance < EPSILON {
+                return Some(s);
+            }
+
+            s += distance * scale;
+            if s > end {
+                return None;
+            }
+        }
+
+        None
+    }
+
+    fn record(&self, ray: &Ray, t: R) -> HitRecord {
+        let point = ray.at(t);
+        HitRecord::new(t, point, self.sdf.normal(point), ray, self.material)
+    }
+}
+
+impl Hittable for SdfShape {
+    fn hit(&self, ray: &Ray, t_min: R, t_max: R) -> Option<HitRecord> {
+        let len = ray.direction().len();
+        let direction = ray.direction() / len;
+        let (start, end) = self.range(ray.origin(), direction, t_min * len, t_max * len)?;
+
+        let s = self.march(ray.origin(), direction, start, end)?;
+
+        closest(vec![self.record(ray, s / len)], t_min, t_max)
+    }
+
+    // Marches through the whole shape, stepping over each surface it finds
+    fn intervals(&self, ray: &Ray) -> Vec<Interval> {
+        let len = ray.direction().len();
+        let direction = ray.direction() / len;
+        let (mut start, end) = match self.range(ray.origin(), direction, -INFINITY, INFINITY) {
+            Some(range) => range,
+            None => return vec![],
+        };
+
+        let mut crossings = vec![];
+        while let Some(s) = self.march(ray.origin(), direction, start, end) {
+            crossings.push(self.record(ray, s / len));
+            start = s + 2. * EPSILON;
+        }
+
+        solid_intervals(crossings)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.bounds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::objects::test_util::{assert_near, material, spans};
+
+    #[test]
+    fn traced_sphere_matches_the_analytic_one() {
+        let center = Vec3::new(0.5, -0.3, 0.2);
+        let traced = SdfShape::new(Sdf::sphere(center, 1.2), material());
+        let sphere = Sphere::new(center, 1.2, material());
+
+        for i in 0..50 {
+            let origin = Vec3::new(-6., (i % 7) as R * 0.3 - 1., (i / 7) as R * 0.3 - 1.);
+            let ray = Ray::new(origin, Vec3::new(3., 0.1, 0.2));
+
+            let expected = sphere.hit(&ray, 0.001, INFINITY);
+            let actual = traced.hit(&ray, 0.001, INFINITY);
+            match (expected, actual) {
+                (Some(expected), Some(actual)) => {
+                    assert_near(expected.t, actual.t, 1e-3);
+                    assert!((expected.normal - actual.normal).len() < 1e-2);
+                    assert!(actual.front_face);
+                }
+                (None, None) => {}
+                // Rays that just touch the sphere
+                (expected, actual) => {
+                    let hr = expected.or(actual).unwrap();
+                    assert!(hr.normal.dot(ray.direction().unit_vector()).abs() < 0.1);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn ray_from_inside_finds_the_way_out() {
+        let shape = SdfShape::new(Sdf::sphere(Vec3::new(0., 0., 0.), 2.), material());
+        let ray = Ray::new(Vec3::new(0., 0., 0.), Vec3::new(0., 0., -0.5));
+
+        let hr = shape.hit(&ray, 0.001, INFINITY).unwrap();
+
+        assert_near(4., hr.t, 1e-3);
+        assert!(!hr.front_face);
+        assert!((hr.normal - Vec3::new(0., 0., 1.)).len() < 1e-3);
+    }
+
+    #[test]
+    fn hit_respects_range() {
+        let shape = SdfShape::new(Sdf::sphere(Vec3::new(0., 0., 0.), 1.), material());
+        let ray = Ray::new(Vec3::new(-5., 0., 0.), Vec3::new(1., 0., 0.));
+
+        assert!(shape.hit(&ray, 0.001, 3.9).is_none());
+        assert_near(6., shape.hit(&ray, 4.5, INFINITY).unwrap().t, 1e-3);
+        assert!(shape.hit(&ray, 6.5, INFINITY).is_none());
+    }
+
+    #[test]
+    fn round_box_normal_is_the_face_normal() {
+        let shape = SdfShape::new(
+            Sdf::round_box(Vec3::new(0., 0., 0.), Vec3::new(1., 2., 1.), 0.2),
+            material(),
+        );
+        let ray = Ray::new(Vec3::new(0.3, 10., 0.), Vec3::new(0., -1., 0.));
+
+        let hr = shape.hit(&ray, 0.001, INFINITY).unwrap();
+
+        assert_near(8., hr.t, 1e-3);
+        assert!((hr.normal - Vec3::new(0., 1., 0.)).len() < 1e-3);
+    }
+
+    #[test]
+    fn smooth_union_fills_the_gap_between_shapes() {
+        let a = Sdf::sphere(Vec3::new(-1.1, 0., 0.), 1.);
+        let b = Sdf::sphere(Vec3::new(1.1, 0., 0.), 1.);
+        // Straight through the gap between the two
+        let ray = Ray::new(Vec3::new(0., -5., 0.), Vec3::new(0., 1., 0.));
+
+        let union = SdfShape::new(a.clone().smooth_union(b.clone(), 0.), material());
+        assert!(union.hit(&ray, 0.001, INFINITY).is_none());
+
+        let blended = SdfShape::new(a.smooth_union(b, 0.5), material());
+        let hr = blended.hit(&ray, 0.001, INFINITY).unwrap();
+        assert!(hr.t < 5.);
+        assert!(blended.bounding_box().unwrap().contains(hr.point));
+    }
+
+    #[test]
+    fn repeated_shapes_appear_every_period() {
+        let sdf = Sdf::sphere(Vec3::new(0., 0., 0.), 0.5).repeat(Vec3::new(3., 0., 3.));
+        assert!(sdf.bounds().is_none());
+
+        let bounds = Aabb::new(Vec3::new(-10., -1., -10.), Vec3::new(10., 1., 10.));
+        let shape = SdfShape::new(sdf, material()).with_bounds(bounds);
+        assert_eq!(Some(bounds), shape.bounding_box());
+
+        // Starts in the middle of the first copy, the last one is at x = 9
+        let ray = Ray::new(Vec3::new(-9., 0., 6.), Vec3::new(1., 0., 0.));
+        let actual = spans(&shape, &ray);
+        assert_eq!(7, actual.len(), "{:?}", actual);
+        for (i, (enter, exit)) in actual.into_iter().enumerate() {
+            assert_near(i as R * 3. - 0.5, enter, 1e-3);
+            assert_near(i as R * 3. + 0.5, exit, 1e-3);
+        }
+        assert_near(0.5, shape.hit(&ray, 0.001, INFINITY).unwrap().t, 1e-3);
+    }
+
+    #[test]
+    fn displaced_hits_are_on_the_bumpy_surface() {
+        let sdf = Sdf::sphere(Vec3::new(0., 0., 0.), 1.).displace(0.1, 8.);
+        let shape = SdfShape::new(sdf.clone(), material());
+        let bounds = shape.bounding_box().unwrap();
+        assert_eq!(Vec3::new(1.1, 1.1, 1.1), bounds.max);
+
+        let mut moved = 0;
+        for i in 0..100 {
+            let target = Vec3::new((i % 10) as R * 0.1 - 0.5, (i / 10) as R * 0.1 - 0.5, 0.);
+            let origin = Vec3::new(0.3, 0.2, 5.);
+            let ray = Ray::new(origin, target - origin);
+
+            let hr = shape.hit(&ray, 0.001, INFINITY).unwrap();
+            assert!(sdf.distance(hr.point).abs() < 1e-3);
+            assert!(bounds.contains(hr.point));
+            if (hr.point.len() - 1.).abs() > 0.01 {
+                moved += 1;
+            }
+        }
+
+        assert!(moved > 50, "only {} hits moved", moved);
+    }
+
+    #[test]
+    fn torus_has_a_hole() {
+        let shape = SdfShape::new(Sdf::torus(Vec3::new(0., 0., 0.), 2., 0.5), material());
+
+        let down = Ray::new(Vec3::new(0., 5., 0.), Vec3::new(0., -1., 0.));
+        assert!(shape.hit(&down, 0.001, INFINITY).is_none());
+
+        let across = Ray::new(Vec3::new(-10., 0., 0.), Vec3::new(1., 0., 0.));
+        let actual = spans(&shape, &across);
+        assert_eq!(2, actual.len(), "{:?}", actual);
+        assert_near(7.5, actual[0].0, 1e-3);
+        assert_near(12.5, actual[1].1, 1e-3);
+    }
+}
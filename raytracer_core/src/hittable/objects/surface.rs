@@ -0,0 +1,159 @@
+// Shared by the analytic primitives, which are all solved in a frame where their axis is y. Each
+// one's `crossings` lists every place the line through the ray crosses its surface, sorted by t,
+// for `closest` and `solid_intervals` to pick from.
+use super::*;
+use crate::math::PI;
+
+#[derive(Copy, Clone, Debug)]
+pub struct Frame {
+    origin: Vec3,
+    x: Vec3,
+    y: Vec3,
+    z: Vec3,
+}
+
+impl Frame {
+    pub fn new(origin: Vec3, axis: Vec3) -> Self {
+        let y = axis.unit_vector();
+        // Anything not too close to the axis
+        let helper = if y.x.abs() > 0.9 {
+            Vec3::new(0., 0., 1.)
+        } else {
+            Vec3::new(1., 0., 0.)
+        };
+        let z = helper.cross(y).unit_vector();
+        let x = y.cross(z);
+
+        Self { origin, x, y, z }
+    }
+
+    // Rotating and moving the ray doesn't change its parameter, so t can be used as is
+    pub fn local_ray(&self, ray: &Ray) -> Ray {
+        let local = |v: Vec3| Vec3::new(v.dot(self.x), v.dot(self.y), v.dot(self.z));
+        Ray::new(local(ray.origin() - self.origin), local(ray.direction()))
+    }
+
+    pub fn world_direction(&self, direction: Vec3) -> Vec3 {
+        self.x * direction.x + self.y * direction.y + self.z * direction.z
+    }
+
+    // Half the size of the box around a circle of that radius around the axis
+    pub fn circle_extent(&self, radius: R) -> Vec3 {
+        let extent = |axis: usize| radius * (1. - self.y[axis] * self.y[axis]).max(0.).sqrt();
+        Vec3::new(extent(0), extent(1), extent(2))
+    }
+
+    pub fn circle_bounds(&self, center: Vec3, radius: R) -> Aabb {
+        let extent = self.circle_extent(radius);
+        Aabb::new(center - extent, center + extent)
+    }
+
+    pub fn axis(&self) -> Vec3 {
+        self.y
+    }
+}
+
+// Where the ray crosses the plane y = height within radius of the axis
+pub fn cap(local: &Ray, height: R, radius: R) -> Option<(R, Vec3)> {
+    if local.direction().y == 0. {
+        return None;
+    }
+
+    let t = (height - local.origin().y) / local.direction().y;
+    let p = local.at(t);
+    if p.x * p.x + p.z * p.z > radius * radius {
+        return None;
+    }
+
+    Some((t, p))
+}
+
+// Texture coordinates of a flat circle, as if the texture were laid on top of it
+pub fn cap_uv(p: Vec3, radius: R) -> (R, R) {
+    (p.x / (2. * radius) + 0.5, p.z / (2. * radius) + 0.5)
+}
+
+// Angle around the axis, from 0 to 1
+pub fn polar(x: R, z: R) -> R {
+    (z.atan2(x) / (2. * PI)).rem_euclid(1.)
+}
+
+// First of the crossings, sorted by t, within range
+pub fn closest(crossings: Vec<HitRecord>, t_min: R, t_max: R) -> Option<HitRecord> {
+    crossings
+        .into_iter()
+        .find(|hr| t_min <= hr.t && hr.t <= t_max)
+}
+
+// Pairs up the crossings, sorted by t, of a closed surface: the ray goes in through front faces and
+// out through back faces
+pub fn solid_intervals(crossings: Vec<HitRecord>) -> Vec<Interval> {
+    // Counts how deep the ray is, so surfaces made of several overlapping shells still pair up
+    let mut intervals = vec![];
+    let mut depth = 0;
+    let mut enter = None;
+    for hr in crossings {
+        if hr.front_face {
+            depth += 1;
+            if depth == 1 {
+                enter = Some(hr);
+            }
+        } else if depth > 0 {
+            depth -= 1;
+            if depth == 0 {
+                if let Some(enter) = enter.take() {
+                    intervals.push(Interval { enter, exit: hr });
+                }
+            }
+        }
+    }
+
+    intervals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::objects::test_util::assert_near;
+
+    #[test]
+    fn frame_keeps_the_ray_parameter() {
+        let frame = Frame::new(Vec3::new(1., 2., 3.), Vec3::new(1., 1., 0.));
+        let ray = Ray::new(Vec3::new(-2., 0., 1.), Vec3::new(0.5, 3., -1.));
+
+        let local = frame.local_ray(&ray);
+
+        assert_near(
+            ray.at(2.5) - Vec3::new(1., 2., 3.),
+            frame.world_direction(local.at(2.5)),
+            1e-5,
+        );
+        assert_near(
+            Vec3::new(0., (2. as R).sqrt(), 0.),
+            frame
+                .local_ray(&Ray::new(Vec3::new(2., 3., 3.), Vec3::one()))
+                .origin(),
+            1e-5,
+        );
+    }
+
+    #[test]
+    fn circle_extent_of_tilted_circle() {
+        let frame = Frame::new(Vec3::new(0., 0., 0.), Vec3::new(0., 1., 1.));
+
+        let s = 0.5 as R;
+        assert_near(
+            Vec3::new(2., 2. * s.sqrt(), 2. * s.sqrt()),
+            frame.circle_extent(2.),
+            1e-5,
+        );
+    }
+
+    #[test]
+    fn polar_goes_around_once() {
+        assert_eq!(0., polar(1., 0.));
+        assert_eq!(0.25, polar(0., 1.));
+        assert_eq!(0.5, polar(-1., 0.));
+        assert_eq!(0.75, polar(0., -1.));
+    }
+}
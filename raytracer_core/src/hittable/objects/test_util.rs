@@ -0,0 +1,59 @@
+// Fixtures and checks shared by the objects' tests
+use super::*;
+use rand::rngs::StdRng;
+use rand::Rng;
+
+// For tests that only care where things are hit
+pub fn material() -> Material {
+    Material::Lambertian {
+        albedo: Color::new(1., 1., 1., 1.),
+    }
+}
+
+// A big cube spanning -1..1 with smaller ones above and beside it
+pub fn cubes_path() -> String {
+    concat!(env!("CARGO_MANIFEST_DIR"), "/res/cubes.obj").into()
+}
+
+// Where the ray goes in and out of the hittable
+pub fn spans(hittable: &dyn Hittable, ray: &Ray) -> Vec<(R, R)> {
+    hittable
+        .intervals(ray)
+        .iter()
+        .map(|i| (i.enter.t, i.exit.t))
+        .collect()
+}
+
+pub trait Near: Copy + std::fmt::Debug {
+    fn distance(self, other: Self) -> R;
+}
+
+impl Near for R {
+    fn distance(self, other: Self) -> R {
+        (self - other).abs()
+    }
+}
+
+impl Near for Vec3 {
+    fn distance(self, other: Self) -> R {
+        (self - other).len()
+    }
+}
+
+pub fn assert_near<T: Near>(expected: T, actual: T, tolerance: R) {
+    assert!(
+        expected.distance(actual) < tolerance,
+        "{:?} is not within {} of {:?}",
+        actual,
+        tolerance,
+        expected
+    );
+}
+
+pub fn random_vec(rng: &mut StdRng, min: R, max: R) -> Vec3 {
+    Vec3::new(
+        rng.gen_range(min, max),
+        rng.gen_range(min, max),
+        rng.gen_range(min, max),
+    )
+}
@@ -0,0 +1,199 @@
+use super::surface::{closest, polar, solid_intervals, Frame};
+use super::*;
+use crate::math::{quartic_roots, NumberOps, PI};
+
+// Ring around `axis`, with the middle of the tube `major` from the center and the tube `minor`
+// thick
+#[derive(Copy, Clone, Debug)]
+pub struct Torus {
+    center: Vec3,
+    frame: Frame,
+    major: R,
+    minor: R,
+    material: Material,
+}
+
+impl Torus {
+    pub fn new(center: Vec3, axis: Vec3, major: R, minor: R, material: Material) -> Self {
+        Self {
+            center,
+            frame: Frame::new(center, axis),
+            major,
+            minor,
+            material,
+        }
+    }
+
+    fn crossings(&self, ray: &Ray) -> Vec<HitRecord> {
+        let local = self.frame.local_ray(ray);
+        let o = local.origin();
+        let d = local.direction();
+        let (ox, oy, oz) = (o.x.to_f64(), o.y.to_f64(), o.z.to_f64());
+        let (dx, dy, dz) = (d.x.to_f64(), d.y.to_f64(), d.z.to_f64());
+        let (major, minor) = (self.major.to_f64(), self.minor.to_f64());
+
+        // Solved along a unit direction from the point closest to the center, which keeps the
+        // coefficients small
+        let len = (dx * dx + dy * dy + dz * dz).sqrt();
+        if len == 0. {
+            return vec![];
+        }
+        let (dx, dy, dz) = (dx / len, dy / len, dz / len);
+        let t0 = -(ox * dx + oy * dy + oz * dz);
+        let (cx, cy, cz) = (ox + t0 * dx, oy + t0 * dy, oz + t0 * dz);
+
+        // (|p|² + R² - r²)² = 4 R² (px² + pz²) with p = c + s d
+        let m = cx * dx + cy * dy + cz * dz;
+        let k = cx * cx + cy * cy + cz * cz + major * major - minor * minor;
+        let four_r2 = 4. * major * major;
+        let b = 4. * m;
+        let c = 4. * m * m + 2. * k - four_r2 * (dx * dx + dz * dz);
+        let d = 4. * m * k - 2. * four_r2 * (cx * dx + cz * dz);
+        let e = k * k - four_r2 * (cx * cx + cz * cz);
+
+        quartic_roots(b, c, d, e)
+            .into_iter()
+            .map(|s| {
+                let t = R::from_f64((t0 + s) / len);
+                let p = local.at(t);
+
+                // Away from the middle of the tube
+                let ring = (p.x * p.x + p.z * p.z).sqrt();
+                let middle = if ring > 0. {
+                    Vec3::new(p.x, 0., p.z) * (self.major / ring)
+                } else {
+                    Vec3::new(self.major, 0., 0.)
+                };
+                let normal = self.frame.world_direction((p - middle).unit_vector());
+
+                let mut hr = HitRecord::new(t, ray.at(t), normal, ray, self.material);
+                let around_tube = (p.y.atan2(ring - self.major) / (2. * PI)).rem_euclid(1.);
+                hr.uv = (polar(p.x, p.z), around_tube);
+                hr
+            })
+            .collect()
+    }
+}
+
+impl Hittable for Torus {
+    fn hit(&self, ray: &Ray, t_min: R, t_max: R) -> Option<HitRecord> {
+        closest(self.crossings(ray), t_min, t_max)
+    }
+
+    fn intervals(&self, ray: &Ray) -> Vec<Interval> {
+        solid_intervals(self.crossings(ray))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let tube = Vec3::new(self.minor, self.minor, self.minor);
+        let ring = self.frame.circle_bounds(self.center, self.major);
+        Some(Aabb::new(ring.min - tube, ring.max + tube))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::objects::test_util::{assert_near, material, random_vec, spans};
+    use crate::math::INFINITY;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    // Lying flat around the origin
+    fn torus() -> Torus {
+        Torus::new(
+            Vec3::new(0., 0., 0.),
+            Vec3::new(0., 1., 0.),
+            2.,
+            0.5,
+            material(),
+        )
+    }
+
+    #[test]
+    fn ray_through_the_middle_crosses_the_tube_twice() {
+        let ray = Ray::new(Vec3::new(-10., 0., 0.), Vec3::new(2., 0., 0.));
+
+        let spans = spans(&torus(), &ray);
+
+        assert_eq!(2, spans.len());
+        for (expected, actual) in [(3.75, 4.25), (5.75, 6.25)].iter().zip(spans) {
+            assert_near(expected.0, actual.0, 1e-4);
+            assert_near(expected.1, actual.1, 1e-4);
+        }
+    }
+
+    #[test]
+    fn torus_hit_normal_and_uv() {
+        let ray = Ray::new(Vec3::new(-10., 0., 0.), Vec3::new(1., 0., 0.));
+
+        let hr = torus().hit(&ray, 0.001, INFINITY).unwrap();
+
+        assert_near(7.5, hr.t, 1e-4);
+        assert!((hr.normal - Vec3::new(-1., 0., 0.)).len() < 1e-4);
+        assert_near(0.5, hr.uv.0, 1e-4);
+        assert_near(0., hr.uv.1.min(1. - hr.uv.1), 1e-4);
+
+        // The top of the tube is a quarter of the way around it
+        let ray = Ray::new(Vec3::new(2., 5., 0.), Vec3::new(0., -1., 0.));
+        let hr = torus().hit(&ray, 0.001, INFINITY).unwrap();
+        assert_near(4.5, hr.t, 1e-4);
+        assert!((hr.normal - Vec3::new(0., 1., 0.)).len() < 1e-4);
+        assert_near(0.25, hr.uv.1, 1e-4);
+    }
+
+    #[test]
+    fn ray_through_the_hole_misses() {
+        let ray = Ray::new(Vec3::new(0., 5., 0.), Vec3::new(0., -1., 0.));
+
+        assert!(torus().hit(&ray, 0.001, INFINITY).is_none());
+        assert!(torus().intervals(&ray).is_empty());
+    }
+
+    #[test]
+    fn torus_hits_are_on_the_tube_and_in_the_box() {
+        let torus = Torus::new(
+            Vec3::new(1., -1., 2.),
+            Vec3::new(1., 2., -0.5),
+            1.5,
+            0.4,
+            Material::Dielectric { ir: 1.5 },
+        );
+        let aabb = torus.bounding_box().unwrap();
+
+        let mut rng = StdRng::seed_from_u64(46);
+        let mut hits = 0;
+        for case in 0..1000 {
+            let origin = random_vec(&mut rng, -8., 8.);
+            let target = Vec3::new(1., -1., 2.) + random_vec(&mut rng, -2., 2.);
+            let ray = Ray::new(origin, (target - origin) * rng.gen_range(0.1, 10.));
+
+            if let Some(hr) = torus.hit(&ray, 0.001, INFINITY) {
+                let local = torus
+                    .frame
+                    .local_ray(&Ray::new(hr.point, Vec3::one()))
+                    .origin();
+                let ring = (local.x * local.x + local.z * local.z).sqrt();
+                let tube = ((ring - 1.5) * (ring - 1.5) + local.y * local.y).sqrt();
+                assert!(
+                    (tube - 0.4).abs() < 1e-3,
+                    "case {}: {} off the tube",
+                    case,
+                    tube - 0.4
+                );
+
+                let slack = Vec3::new(1e-4, 1e-4, 1e-4);
+                assert!(
+                    Aabb::new(aabb.min - slack, aabb.max + slack).contains(hr.point),
+                    "case {}: {:?} outside {:?}",
+                    case,
+                    hr.point,
+                    aabb
+                );
+                hits += 1;
+            }
+        }
+
+        assert!(hits > 100, "only {} hits", hits);
+    }
+}
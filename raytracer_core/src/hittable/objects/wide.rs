@@ -0,0 +1,763 @@
+// Intersects one ray with packs of LANES triangles or spheres at once. The packs are stored as
+// structures of arrays, so AVX handles a whole pack per instruction and SSE half of one. Other
+// architectures and f64 builds run the same kernels one lane at a time.
+use crate::math::{Vec3, INFINITY, R};
+use crate::ray::Ray;
+
+pub const LANES: usize = 8;
+
+// Instruction set a Mesh or Spheres intersects its packs with. They use the best one the CPU supports
+// unless told otherwise, and fall back to Scalar for one the CPU or build doesn't have.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Simd {
+    Scalar,
+    Sse,
+    Avx,
+}
+
+impl Simd {
+    // Widest instruction set this CPU supports
+    pub fn best() -> Self {
+        if Simd::Avx.is_available() {
+            Simd::Avx
+        } else if Simd::Sse.is_available() {
+            Simd::Sse
+        } else {
+            Simd::Scalar
+        }
+    }
+
+    pub fn is_available(self) -> bool {
+        match self {
+            Simd::Scalar => true,
+            #[cfg(all(target_arch = "x86_64", not(feature = "f64")))]
+            // SSE2 is part of x86_64
+            Simd::Sse => true,
+            #[cfg(all(target_arch = "x86_64", not(feature = "f64")))]
+            Simd::Avx => is_x86_feature_detected!("avx"),
+            #[cfg(not(all(target_arch = "x86_64", not(feature = "f64"))))]
+            _ => false,
+        }
+    }
+
+    // Falls back to scalar code when the CPU or build doesn't support the instruction set
+    pub fn or_scalar(self) -> Self {
+        if self.is_available() {
+            self
+        } else {
+            Simd::Scalar
+        }
+    }
+}
+
+impl Default for Simd {
+    fn default() -> Self {
+        Simd::best()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+struct Vec3Lanes {
+    x: [R; LANES],
+    y: [R; LANES],
+    z: [R; LANES],
+}
+
+impl Vec3Lanes {
+    fn set(&mut self, lane: usize, v: Vec3) {
+        self.x[lane] = v.x;
+        self.y[lane] = v.y;
+        self.z[lane] = v.z;
+    }
+
+    fn axis(&self, axis: usize) -> &[R; LANES] {
+        match axis {
+            0 => &self.x,
+            1 => &self.y,
+            _ => &self.z,
+        }
+    }
+
+    // The axes in the order the ray shears them
+    fn sheared(&self, ray: &ShearedRay) -> (&[R; LANES], &[R; LANES], &[R; LANES]) {
+        (self.axis(ray.kx), self.axis(ray.ky), self.axis(ray.kz))
+    }
+}
+
+// A ray moved to the origin and sheared so it points down +z, which turns the triangle test into
+// 2D edge functions around the origin (Woop et al., "Watertight Ray/Triangle Intersection").
+// Vertices shared by triangles are transformed the same for each of them, so neighbours agree on
+// which side of their shared edge the ray passes.
+pub struct ShearedRay {
+    origin: Vec3,
+    // Axes of the sheared x, y and z, z is where the direction is largest
+    kx: usize,
+    ky: usize,
+    kz: usize,
+    sx: R,
+    sy: R,
+    sz: R,
+}
+
+impl ShearedRay {
+    pub fn new(ray: &Ray) -> Self {
+        let d = ray.direction();
+        let (x, y, z) = (d.x.abs(), d.y.abs(), d.z.abs());
+        let kz = if x > y && x > z {
+            0
+        } else if y > z {
+            1
+        } else {
+            2
+        };
+        let (mut kx, mut ky) = ((kz + 1) % 3, (kz + 2) % 3);
+        // Keeps the winding of the triangles when z flips
+        if d[kz] < 0. {
+            std::mem::swap(&mut kx, &mut ky);
+        }
+
+        Self {
+            origin: ray.origin(),
+            kx,
+            ky,
+            kz,
+            sx: d[kx] / d[kz],
+            sy: d[ky] / d[kz],
+            sz: 1. / d[kz],
+        }
+    }
+
+    // The sheared z is scaled so it is the ray's parametric t
+    pub fn shear(&self, v: Vec3) -> (R, R, R) {
+        let v = v - self.origin;
+        (
+            v[self.kx] - self.sx * v[self.kz],
+            v[self.ky] - self.sy * v[self.kz],
+            self.sz * v[self.kz],
+        )
+    }
+}
+
+// Unused lanes are zero sized triangles, which the ray never hits
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TrianglePack {
+    v0: Vec3Lanes,
+    v1: Vec3Lanes,
+    v2: Vec3Lanes,
+    len: usize,
+}
+
+impl TrianglePack {
+    pub fn pack(triangles: &[(Vec3, Vec3, Vec3)]) -> Vec<Self> {
+        triangles
+            .chunks(LANES)
+            .map(|chunk| {
+                let mut pack = Self {
+                    len: chunk.len(),
+                    ..Self::default()
+                };
+                for (lane, (v0, v1, v2)) in chunk.iter().enumerate() {
+                    pack.v0.set(lane, *v0);
+                    pack.v1.set(lane, *v1);
+                    pack.v2.set(lane, *v2);
+                }
+                pack
+            })
+            .collect()
+    }
+
+    // Parametric t of the closest hit between t_min and t_max and the lane it's in, plus a bit mask
+    // of the lanes the ray passes exactly through an edge or vertex of. Those are left to the
+    // scalar test in mesh.rs, which decides which of the neighbouring triangles gets the hit.
+    pub fn hit(
+        &self,
+        simd: Simd,
+        ray: &ShearedRay,
+        t_min: R,
+        t_max: R,
+    ) -> (Option<(R, usize)>, u32) {
+        let mut t = [INFINITY; LANES];
+        let (hits, on_edge) = match simd {
+            #[cfg(all(target_arch = "x86_64", not(feature = "f64")))]
+            Simd::Avx => unsafe { x86::triangles_avx(self, ray, t_min, t_max, &mut t) },
+            #[cfg(all(target_arch = "x86_64", not(feature = "f64")))]
+            Simd::Sse => unsafe { x86::triangles_sse(self, ray, t_min, t_max, &mut t) },
+            _ => unsafe { triangles::<Scalar>(self, ray, t_min, t_max, &mut t) },
+        };
+
+        (closest(&t, hits, self.len), on_edge & used(self.len))
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SpherePack {
+    center: Vec3Lanes,
+    radius: [R; LANES],
+    len: usize,
+}
+
+impl SpherePack {
+    pub fn pack(spheres: &[(Vec3, R)]) -> Vec<Self> {
+        spheres
+            .chunks(LANES)
+            .map(|chunk| {
+                let mut pack = Self {
+                    len: chunk.len(),
+                    ..Self::default()
+                };
+                for (lane, (center, radius)) in chunk.iter().enumerate() {
+                    pack.center.set(lane, *center);
+                    pack.radius[lane] = *radius;
+                }
+                pack
+            })
+            .collect()
+    }
+
+    // Closest hit between t_min and t_max and the lane it's in
+    pub fn hit(&self, simd: Simd, ray: &Ray, t_min: R, t_max: R) -> Option<(R, usize)> {
+        let mut t = [INFINITY; LANES];
+        let hits = match simd {
+            #[cfg(all(target_arch = "x86_64", not(feature = "f64")))]
+            Simd::Avx => unsafe { x86::spheres_avx(self, ray, t_min, t_max, &mut t) },
+            #[cfg(all(target_arch = "x86_64", not(feature = "f64")))]
+            Simd::Sse => unsafe { x86::spheres_sse(self, ray, t_min, t_max, &mut t) },
+            _ => unsafe { spheres::<Scalar>(self, ray, t_min, t_max, &mut t) },
+        };
+
+        closest(&t, hits, self.len)
+    }
+}
+
+// Indices of the bits set in a lane mask, in order
+pub fn lanes(mut mask: u32) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        if mask == 0 {
+            return None;
+        }
+        let lane = mask.trailing_zeros() as usize;
+        mask &= mask - 1;
+        Some(lane)
+    })
+}
+
+fn used(len: usize) -> u32 {
+    (1 << len) - 1
+}
+
+// Picks from the lanes set in the hits bit mask, most rays miss every lane so this is usually
+// skipped. Ties go to the later lane, like the scalar loops that replace the closest hit on `<=`.
+fn closest(t: &[R; LANES], hits: u32, len: usize) -> Option<(R, usize)> {
+    let mut closest: Option<(R, usize)> = None;
+    for lane in lanes(hits & used(len)) {
+        if closest.is_none_or(|(closest, _)| t[lane] <= closest) {
+            closest = Some((t[lane], lane));
+        }
+    }
+
+    closest
+}
+
+/// A register of WIDTH lanes.
+///
+/// # Safety
+///
+/// The methods may use instructions the CPU doesn't have, callers check it supports them first.
+unsafe trait Wide: Copy {
+    const WIDTH: usize;
+    type Mask: Copy;
+
+    unsafe fn splat(x: R) -> Self;
+    unsafe fn load(lanes: &[R]) -> Self;
+    unsafe fn store(self, lanes: &mut [R]);
+
+    unsafe fn add(self, rhs: Self) -> Self;
+    unsafe fn sub(self, rhs: Self) -> Self;
+    unsafe fn mul(self, rhs: Self) -> Self;
+    unsafe fn div(self, rhs: Self) -> Self;
+    unsafe fn sqrt(self) -> Self;
+
+    unsafe fn eq(self, rhs: Self) -> Self::Mask;
+    unsafe fn lt(self, rhs: Self) -> Self::Mask;
+    unsafe fn le(self, rhs: Self) -> Self::Mask;
+    unsafe fn gt(self, rhs: Self) -> Self::Mask;
+    unsafe fn ge(self, rhs: Self) -> Self::Mask;
+    unsafe fn and(a: Self::Mask, b: Self::Mask) -> Self::Mask;
+    unsafe fn or(a: Self::Mask, b: Self::Mask) -> Self::Mask;
+    // Lanes of a where the mask is set, b everywhere else
+    unsafe fn select(mask: Self::Mask, a: Self, b: Self) -> Self;
+    // One bit per lane, set where the mask is
+    unsafe fn bits(mask: Self::Mask) -> u32;
+}
+
+#[derive(Copy, Clone)]
+struct Wide3<W> {
+    x: W,
+    y: W,
+    z: W,
+}
+
+impl<W: Wide> Wide3<W> {
+    #[inline(always)]
+    unsafe fn splat(v: Vec3) -> Self {
+        Self {
+            x: W::splat(v.x),
+            y: W::splat(v.y),
+            z: W::splat(v.z),
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn load(lanes: &Vec3Lanes, offset: usize) -> Self {
+        Self {
+            x: W::load(&lanes.x[offset..]),
+            y: W::load(&lanes.y[offset..]),
+            z: W::load(&lanes.z[offset..]),
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn sub(self, rhs: Self) -> Self {
+        Self {
+            x: self.x.sub(rhs.x),
+            y: self.y.sub(rhs.y),
+            z: self.z.sub(rhs.z),
+        }
+    }
+
+    // Same order of operations as Vec3, so every instruction set gives the same result
+    #[inline(always)]
+    unsafe fn dot(self, rhs: Self) -> W {
+        self.x
+            .mul(rhs.x)
+            .add(self.y.mul(rhs.y))
+            .add(self.z.mul(rhs.z))
+    }
+}
+
+// ShearedRay in registers, hoisted out of the loops over the lanes
+struct WideShear<W> {
+    origin: (W, W, W),
+    shear: (W, W, W),
+}
+
+impl<W: Wide> WideShear<W> {
+    #[inline(always)]
+    unsafe fn new(ray: &ShearedRay) -> Self {
+        Self {
+            origin: (
+                W::splat(ray.origin[ray.kx]),
+                W::splat(ray.origin[ray.ky]),
+                W::splat(ray.origin[ray.kz]),
+            ),
+            shear: (W::splat(ray.sx), W::splat(ray.sy), W::splat(ray.sz)),
+        }
+    }
+
+    // Same steps as ShearedRay::shear, for vertices already in the ray's axis order
+    #[inline(always)]
+    unsafe fn apply(
+        &self,
+        axes: (&[R; LANES], &[R; LANES], &[R; LANES]),
+        offset: usize,
+    ) -> (W, W, W) {
+        let x = W::load(&axes.0[offset..]).sub(self.origin.0);
+        let y = W::load(&axes.1[offset..]).sub(self.origin.1);
+        let z = W::load(&axes.2[offset..]).sub(self.origin.2);
+
+        (
+            x.sub(self.shear.0.mul(z)),
+            y.sub(self.shear.1.mul(z)),
+            self.shear.2.mul(z),
+        )
+    }
+}
+
+// The watertight test with the same operations as intersect_ray_triangle in mesh.rs, writing t for
+// the lanes that hit. Returns those lanes and the ones where an edge function is exactly zero as
+// bit masks.
+#[inline(always)]
+unsafe fn triangles<W: Wide>(
+    pack: &TrianglePack,
+    ray: &ShearedRay,
+    t_min: R,
+    t_max: R,
+    out: &mut [R; LANES],
+) -> (u32, u32) {
+    let shear = WideShear::<W>::new(ray);
+    let (v0, v1, v2) = (
+        pack.v0.sheared(ray),
+        pack.v1.sheared(ray),
+        pack.v2.sheared(ray),
+    );
+    let zero = W::splat(0.);
+    let t_min = W::splat(t_min);
+    let t_max = W::splat(t_max);
+
+    let (mut hits, mut on_edge) = (0, 0);
+    for offset in (0..LANES).step_by(W::WIDTH) {
+        let (ax, ay, az) = shear.apply(v0, offset);
+        let (bx, by, bz) = shear.apply(v1, offset);
+        let (cx, cy, cz) = shear.apply(v2, offset);
+
+        let u = cx.mul(by).sub(cy.mul(bx));
+        let v = ax.mul(cy).sub(ay.mul(cx));
+        let w = bx.mul(ay).sub(by.mul(ax));
+
+        let det = u.add(v).add(w);
+        let t = u.mul(az).add(v.mul(bz)).add(w.mul(cz)).div(det);
+
+        let front = W::and(W::and(u.gt(zero), v.gt(zero)), w.gt(zero));
+        let back = W::and(W::and(u.lt(zero), v.lt(zero)), w.lt(zero));
+        let in_range = W::and(t.ge(t_min), t.le(t_max));
+        let hit = W::and(W::or(front, back), in_range);
+        let edge = W::or(W::or(u.eq(zero), v.eq(zero)), w.eq(zero));
+
+        t.store(&mut out[offset..]);
+        hits |= W::bits(hit) << offset;
+        on_edge |= W::bits(edge) << offset;
+    }
+
+    (hits, on_edge)
+}
+
+// Near root of the ray/sphere quadratic, or the far one when the near one is out of range like in
+// Sphere::hit, writing t for the lanes that hit between t_min and t_max and returning them as a bit
+// mask
+#[inline(always)]
+unsafe fn spheres<W: Wide>(
+    pack: &SpherePack,
+    ray: &Ray,
+    t_min: R,
+    t_max: R,
+    out: &mut [R; LANES],
+) -> u32 {
+    let origin = Wide3::<W>::splat(ray.origin());
+    let direction = Wide3::<W>::splat(ray.direction());
+    let a = W::splat(ray.direction().dot(ray.direction()));
+    let zero = W::splat(0.);
+    let t_min = W::splat(t_min);
+    let t_max = W::splat(t_max);
+
+    let mut hits = 0;
+    for offset in (0..LANES).step_by(W::WIDTH) {
+        let center = Wide3::load(&pack.center, offset);
+        let radius = W::load(&pack.radius[offset..]);
+
+        let oc = origin.sub(center);
+        let half_b = oc.dot(direction);
+        let c = oc.dot(oc).sub(radius.mul(radius));
+        let discriminant = half_b.mul(half_b).sub(a.mul(c));
+
+        let sqrtd = discriminant.sqrt();
+        let near = zero.sub(half_b).sub(sqrtd).div(a);
+        let far = zero.sub(half_b).add(sqrtd).div(a);
+        let near_in_range = W::and(near.ge(t_min), near.le(t_max));
+        let far_in_range = W::and(far.ge(t_min), far.le(t_max));
+
+        let root = W::select(near_in_range, near, far);
+        let hit = W::and(discriminant.ge(zero), W::or(near_in_range, far_in_range));
+
+        root.store(&mut out[offset..]);
+        hits |= W::bits(hit) << offset;
+    }
+
+    hits
+}
+
+#[derive(Copy, Clone)]
+struct Scalar(R);
+
+unsafe impl Wide for Scalar {
+    const WIDTH: usize = 1;
+    type Mask = bool;
+
+    #[inline(always)]
+    unsafe fn splat(x: R) -> Self {
+        Scalar(x)
+    }
+    #[inline(always)]
+    unsafe fn load(lanes: &[R]) -> Self {
+        Scalar(lanes[0])
+    }
+    #[inline(always)]
+    unsafe fn store(self, lanes: &mut [R]) {
+        lanes[0] = self.0;
+    }
+
+    #[inline(always)]
+    unsafe fn add(self, rhs: Self) -> Self {
+        Scalar(self.0 + rhs.0)
+    }
+    #[inline(always)]
+    unsafe fn sub(self, rhs: Self) -> Self {
+        Scalar(self.0 - rhs.0)
+    }
+    #[inline(always)]
+    unsafe fn mul(self, rhs: Self) -> Self {
+        Scalar(self.0 * rhs.0)
+    }
+    #[inline(always)]
+    unsafe fn div(self, rhs: Self) -> Self {
+        Scalar(self.0 / rhs.0)
+    }
+    #[inline(always)]
+    unsafe fn sqrt(self) -> Self {
+        Scalar(self.0.sqrt())
+    }
+
+    #[inline(always)]
+    unsafe fn eq(self, rhs: Self) -> bool {
+        self.0 == rhs.0
+    }
+    #[inline(always)]
+    unsafe fn lt(self, rhs: Self) -> bool {
+        self.0 < rhs.0
+    }
+    #[inline(always)]
+    unsafe fn le(self, rhs: Self) -> bool {
+        self.0 <= rhs.0
+    }
+    #[inline(always)]
+    unsafe fn gt(self, rhs: Self) -> bool {
+        self.0 > rhs.0
+    }
+    #[inline(always)]
+    unsafe fn ge(self, rhs: Self) -> bool {
+        self.0 >= rhs.0
+    }
+    #[inline(always)]
+    unsafe fn and(a: bool, b: bool) -> bool {
+        a & b
+    }
+    #[inline(always)]
+    unsafe fn or(a: bool, b: bool) -> bool {
+        a | b
+    }
+    #[inline(always)]
+    unsafe fn select(mask: bool, a: Self, b: Self) -> Self {
+        if mask {
+            a
+        } else {
+            b
+        }
+    }
+    #[inline(always)]
+    unsafe fn bits(mask: bool) -> u32 {
+        mask as u32
+    }
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "f64")))]
+mod x86 {
+    use super::*;
+    use std::arch::x86_64::*;
+
+    // The kernels are inlined into these, so every intrinsic is compiled for the enabled features
+    #[target_feature(enable = "avx")]
+    pub unsafe fn triangles_avx(
+        pack: &TrianglePack,
+        ray: &ShearedRay,
+        t_min: R,
+        t_max: R,
+        out: &mut [R; LANES],
+    ) -> (u32, u32) {
+        triangles::<Avx>(pack, ray, t_min, t_max, out)
+    }
+
+    #[target_feature(enable = "sse2")]
+    pub unsafe fn triangles_sse(
+        pack: &TrianglePack,
+        ray: &ShearedRay,
+        t_min: R,
+        t_max: R,
+        out: &mut [R; LANES],
+    ) -> (u32, u32) {
+        triangles::<Sse>(pack, ray, t_min, t_max, out)
+    }
+
+    #[target_feature(enable = "avx")]
+    pub unsafe fn spheres_avx(
+        pack: &SpherePack,
+        ray: &Ray,
+        t_min: R,
+        t_max: R,
+        out: &mut [R; LANES],
+    ) -> u32 {
+        spheres::<Avx>(pack, ray, t_min, t_max, out)
+    }
+
+    #[target_feature(enable = "sse2")]
+    pub unsafe fn spheres_sse(
+        pack: &SpherePack,
+        ray: &Ray,
+        t_min: R,
+        t_max: R,
+        out: &mut [R; LANES],
+    ) -> u32 {
+        spheres::<Sse>(pack, ray, t_min, t_max, out)
+    }
+
+    #[derive(Copy, Clone)]
+    struct Sse(__m128);
+
+    unsafe impl Wide for Sse {
+        const WIDTH: usize = 4;
+        type Mask = __m128;
+
+        #[inline(always)]
+        unsafe fn splat(x: R) -> Self {
+            Sse(_mm_set1_ps(x))
+        }
+        #[inline(always)]
+        unsafe fn load(lanes: &[R]) -> Self {
+            assert!(lanes.len() >= Self::WIDTH);
+            Sse(_mm_loadu_ps(lanes.as_ptr()))
+        }
+        #[inline(always)]
+        unsafe fn store(self, lanes: &mut [R]) {
+            assert!(lanes.len() >= Self::WIDTH);
+            _mm_storeu_ps(lanes.as_mut_ptr(), self.0)
+        }
+
+        #[inline(always)]
+        unsafe fn add(self, rhs: Self) -> Self {
+            Sse(_mm_add_ps(self.0, rhs.0))
+        }
+        #[inline(always)]
+        unsafe fn sub(self, rhs: Self) -> Self {
+            Sse(_mm_sub_ps(self.0, rhs.0))
+        }
+        #[inline(always)]
+        unsafe fn mul(self, rhs: Self) -> Self {
+            Sse(_mm_mul_ps(self.0, rhs.0))
+        }
+        #[inline(always)]
+        unsafe fn div(self, rhs: Self) -> Self {
+            Sse(_mm_div_ps(self.0, rhs.0))
+        }
+        #[inline(always)]
+        unsafe fn sqrt(self) -> Self {
+            Sse(_mm_sqrt_ps(self.0))
+        }
+
+        #[inline(always)]
+        unsafe fn eq(self, rhs: Self) -> __m128 {
+            _mm_cmpeq_ps(self.0, rhs.0)
+        }
+        #[inline(always)]
+        unsafe fn lt(self, rhs: Self) -> __m128 {
+            _mm_cmplt_ps(self.0, rhs.0)
+        }
+        #[inline(always)]
+        unsafe fn le(self, rhs: Self) -> __m128 {
+            _mm_cmple_ps(self.0, rhs.0)
+        }
+        #[inline(always)]
+        unsafe fn gt(self, rhs: Self) -> __m128 {
+            _mm_cmpgt_ps(self.0, rhs.0)
+        }
+        #[inline(always)]
+        unsafe fn ge(self, rhs: Self) -> __m128 {
+            _mm_cmpge_ps(self.0, rhs.0)
+        }
+        #[inline(always)]
+        unsafe fn and(a: __m128, b: __m128) -> __m128 {
+            _mm_and_ps(a, b)
+        }
+        #[inline(always)]
+        unsafe fn or(a: __m128, b: __m128) -> __m128 {
+            _mm_or_ps(a, b)
+        }
+        #[inline(always)]
+        unsafe fn select(mask: __m128, a: Self, b: Self) -> Self {
+            // No blendv before SSE4.1
+            Sse(_mm_or_ps(_mm_and_ps(mask, a.0), _mm_andnot_ps(mask, b.0)))
+        }
+        #[inline(always)]
+        unsafe fn bits(mask: __m128) -> u32 {
+            _mm_movemask_ps(mask) as u32
+        }
+    }
+
+    #[derive(Copy, Clone)]
+    struct Avx(__m256);
+
+    unsafe impl Wide for Avx {
+        const WIDTH: usize = 8;
+        type Mask = __m256;
+
+        #[inline(always)]
+        unsafe fn splat(x: R) -> Self {
+            Avx(_mm256_set1_ps(x))
+        }
+        #[inline(always)]
+        unsafe fn load(lanes: &[R]) -> Self {
+            assert!(lanes.len() >= Self::WIDTH);
+            Avx(_mm256_loadu_ps(lanes.as_ptr()))
+        }
+        #[inline(always)]
+        unsafe fn store(self, lanes: &mut [R]) {
+            assert!(lanes.len() >= Self::WIDTH);
+            _mm256_storeu_ps(lanes.as_mut_ptr(), self.0)
+        }
+
+        #[inline(always)]
+        unsafe fn add(self, rhs: Self) -> Self {
+            Avx(_mm256_add_ps(self.0, rhs.0))
+        }
+        #[inline(always)]
+        unsafe fn sub(self, rhs: Self) -> Self {
+            Avx(_mm256_sub_ps(self.0, rhs.0))
+        }
+        #[inline(always)]
+        unsafe fn mul(self, rhs: Self) -> Self {
+            Avx(_mm256_mul_ps(self.0, rhs.0))
+        }
+        #[inline(always)]
+        unsafe fn div(self, rhs: Self) -> Self {
+            Avx(_mm256_div_ps(self.0, rhs.0))
+        }
+        #[inline(always)]
+        unsafe fn sqrt(self) -> Self {
+            Avx(_mm256_sqrt_ps(self.0))
+        }
+
+        #[inline(always)]
+        unsafe fn eq(self, rhs: Self) -> __m256 {
+            _mm256_cmp_ps(self.0, rhs.0, _CMP_EQ_OQ)
+        }
+        #[inline(always)]
+        unsafe fn lt(self, rhs: Self) -> __m256 {
+            _mm256_cmp_ps(self.0, rhs.0, _CMP_LT_OQ)
+        }
+        #[inline(always)]
+        unsafe fn le(self, rhs: Self) -> __m256 {
+            _mm256_cmp_ps(self.0, rhs.0, _CMP_LE_OQ)
+        }
+        #[inline(always)]
+        unsafe fn gt(self, rhs: Self) -> __m256 {
+            _mm256_cmp_ps(self.0, rhs.0, _CMP_GT_OQ)
+        }
+        #[inline(always)]
+        unsafe fn ge(self, rhs: Self) -> __m256 {
+            _mm256_cmp_ps(self.0, rhs.0, _CMP_GE_OQ)
+        }
+        #[inline(always)]
+        unsafe fn and(a: __m256, b: __m256) -> __m256 {
+            _mm256_and_ps(a, b)
+        }
+        #[inline(always)]
+        unsafe fn or(a: __m256, b: __m256) -> __m256 {
+            _mm256_or_ps(a, b)
+        }
+        #[inline(always)]
+        unsafe fn select(mask: __m256, a: Self, b: Self) -> Self {
+            Avx(_mm256_blendv_ps(b.0, a.0, mask))
+        }
+        #[inline(always)]
+        unsafe fn bits(mask: __m256) -> u32 {
+            _mm256_movemask_ps(mask) as u32
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! Types shared by the raytracer and the sprite generator.
+//!
+//! To embed the renderer, build a [`render::Scene`] and pass it to [`render::render`]:
+//!
+//! ```
+//! use raytracer_core::camera::CameraSettings;
+//! use raytracer_core::color::Color;
+//! use raytracer_core::hittable::{materials::Material, objects::{Sphere, World}};
+//! use raytracer_core::lighting::LightingSettings;
+//! use raytracer_core::math::Vec3;
+//! use raytracer_core::render::{render, RenderOptions, Scene};
+//!
+//! let mut world = World::new();
+//! let albedo = Color::new(0.8, 0.2, 0.2, 1.);
+//! world.add_item(Sphere::new(Vec3::new(0., 0., 0.), 1., Material::Lambertian { albedo }));
+//!
+//! let options = RenderOptions::new(32, 32);
+//! let camera = CameraSettings::default().into_camera(options.aspect_ratio());
+//! let scene = Scene {
+//!     camera: &camera,
+//!     world: &world,
+//!     lighting: &LightingSettings::default(),
+//! };
+//!
+//! let image: image::RgbaImage = render(&scene, &options).into();
+//! assert_eq!((32, 32), image.dimensions());
+//! ```
+pub mod camera;
+pub mod color;
+pub mod hittable;
+pub mod lighting;
+pub mod math;
+pub mod projection;
+pub mod ray;
+pub mod render;
+pub mod time;
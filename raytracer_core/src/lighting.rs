@@ -0,0 +1,142 @@
+use super::color::Color;
+use super::hittable::{materials::Material, objects::World, HitRecord, Hittable};
+use super::math::{Vec3, INFINITY, R};
+use super::ray::Ray;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const MIN_HIT: R = 0.0001;
+
+// Directional light, like the sun. Key and fill lights are both just lights with different intensities.
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Light {
+    // Points from the surface towards the light
+    pub direction: Vec3,
+    pub color: Color,
+    pub intensity: R,
+}
+
+// Vertical gradient that bounced rays pick up when they escape the scene
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Sky {
+    pub top: Color,
+    pub bottom: Color,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct LightingSettings {
+    pub max_depth: u32,
+    pub lights: Vec<Light>,
+    pub sky: Option<Sky>,
+}
+
+impl Default for LightingSettings {
+    fn default() -> Self {
+        let white = Color::new(1., 1., 1., 1.);
+
+        Self {
+            max_depth: 8,
+            lights: vec![
+                Light {
+                    direction: Vec3::new(-1., 1., 1.),
+                    color: white,
+                    intensity: 1.,
+                },
+                Light {
+                    direction: Vec3::new(1., 0.25, 0.5),
+                    color: white,
+                    intensity: 0.3,
+                },
+            ],
+            sky: Some(Sky {
+                top: Color::new(0.25, 0.3, 0.35, 1.),
+                bottom: Color::new(0.05, 0.05, 0.05, 1.),
+            }),
+        }
+    }
+}
+
+impl LightingSettings {
+    pub fn shade(&self, ray: &Ray, hr: &HitRecord, world: &World) -> Color {
+        let mut color = self.bounce(ray, hr, world, self.max_depth);
+        color.a = 1.;
+
+        color
+    }
+
+    fn trace(&self, ray: &Ray, world: &World, depth: u32) -> Color {
+        if depth == 0 {
+            return black();
+        }
+
+        match world.hit(ray, MIN_HIT, INFINITY) {
+            Some(hr) => self.bounce(ray, &hr, world, depth),
+            None => self.sky_color(ray),
+        }
+    }
+
+    fn bounce(&self, ray: &Ray, hr: &HitRecord, world: &World, depth: u32) -> Color {
+        let mut color = self.direct(hr, world);
+
+        if let Some((attenuation, scattered)) = hr.material.scatter(ray, hr) {
+            let scattered = scattered.offset(hr.normal);
+            color += attenuation * self.trace(&scattered, world, depth - 1);
+        }
+
+        color
+    }
+
+    // Light arriving straight from the lights. Only diffuse surfaces pick this up, as the chance of a
+    // mirror or glass bouncing exactly towards a directional light is zero.
+    fn direct(&self, hr: &HitRecord, world: &World) -> Color {
+        let albedo = match hr.material {
+            Material::Lambertian { albedo } => albedo,
+            _ => return black(),
+        };
+
+        let mut color = black();
+        for light in &self.lights {
+            let to_light = light.direction.unit_vector();
+            let cos = hr.normal.dot(to_light);
+            if cos <= 0. {
+                continue;
+            }
+
+            let shadow = Ray::new(hr.point, to_light).offset(hr.normal);
+            if world.hit(&shadow, MIN_HIT, INFINITY).is_some() {
+                continue;
+            }
+
+            color += albedo * light.color * (light.intensity * cos);
+        }
+
+        color
+    }
+
+    // Color seen along a camera ray, transparent where it escapes without a sky
+    pub fn radiance(&self, ray: &Ray, world: &World) -> Color {
+        match world.hit(ray, MIN_HIT, INFINITY) {
+            Some(hr) => self.shade(ray, &hr, world),
+            None => self.sky_color(ray),
+        }
+    }
+
+    fn sky_color(&self, ray: &Ray) -> Color {
+        match self.sky {
+            Some(sky) => {
+                let unit_dir = ray.direction().unit_vector();
+                let t = 0.5 * (unit_dir.y + 1.);
+
+                sky.bottom * (1. - t) + sky.top * t
+            }
+            None => black(),
+        }
+    }
+}
+
+fn black() -> Color {
+    Color::new(0., 0., 0., 0.)
+}
@@ -0,0 +1,120 @@
+use super::*;
+
+// Axis aligned bounding box
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    pub fn around(points: &[Vec3]) -> Self {
+        let min = Vec3::new(INFINITY, INFINITY, INFINITY);
+        points.iter().fold(Self::new(min, -min), |aabb, p| {
+            aabb.surrounding(&Self::new(*p, *p))
+        })
+    }
+
+    pub fn surrounding(&self, other: &Self) -> Self {
+        Self::new(
+            Vec3::new(
+                self.min.x.min(other.min.x),
+                self.min.y.min(other.min.y),
+                self.min.z.min(other.min.z),
+            ),
+            Vec3::new(
+                self.max.x.max(other.max.x),
+                self.max.y.max(other.max.y),
+                self.max.z.max(other.max.z),
+            ),
+        )
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) / 2.
+    }
+
+    pub fn contains(&self, p: Vec3) -> bool {
+        (0..3).all(|axis| self.min[axis] <= p[axis] && p[axis] <= self.max[axis])
+    }
+
+    // Where the whole line through origin along direction is inside the box, as multiples of
+    // direction
+    pub fn slab(&self, origin: Vec3, direction: Vec3) -> Option<(R, R)> {
+        let (mut near, mut far) = (-INFINITY, INFINITY);
+        for axis in 0..3 {
+            if direction[axis] == 0. {
+                if origin[axis] < self.min[axis] || self.max[axis] < origin[axis] {
+                    return None;
+                }
+                continue;
+            }
+
+            let a = (self.min[axis] - origin[axis]) / direction[axis];
+            let b = (self.max[axis] - origin[axis]) / direction[axis];
+            near = near.max(a.min(b));
+            far = far.min(a.max(b));
+        }
+
+        if near <= far {
+            Some((near, far))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aabb_around_points_is_tight() {
+        let aabb = Aabb::around(&[
+            Vec3::new(1., -2., 0.),
+            Vec3::new(-1., 0., 3.),
+            Vec3::new(0., 5., 1.),
+        ]);
+
+        assert_eq!(Vec3::new(-1., -2., 0.), aabb.min);
+        assert_eq!(Vec3::new(1., 5., 3.), aabb.max);
+        assert_eq!(Vec3::new(0., 1.5, 1.5), aabb.center());
+    }
+
+    #[test]
+    fn aabb_surrounding_contains_both() {
+        let a = Aabb::new(Vec3::new(0., 0., 0.), Vec3::new(1., 1., 1.));
+        let b = Aabb::new(Vec3::new(2., -1., 0.5), Vec3::new(3., 0., 0.6));
+
+        let both = a.surrounding(&b);
+
+        assert_eq!(
+            Aabb::new(Vec3::new(0., -1., 0.), Vec3::new(3., 1., 1.)),
+            both
+        );
+        assert!(both.contains(Vec3::new(2.5, -0.5, 0.55)));
+        assert!(!both.contains(Vec3::new(3.5, 0., 0.)));
+    }
+
+    #[test]
+    fn aabb_slab_is_where_the_line_is_inside() {
+        let aabb = Aabb::new(Vec3::new(-1., -1., -1.), Vec3::new(1., 2., 1.));
+
+        let slab = aabb.slab(Vec3::new(-5., 0., 0.), Vec3::new(2., 0., 0.));
+        assert_eq!(Some((2., 3.)), slab);
+
+        let slab = aabb.slab(Vec3::new(0., 0., 0.), Vec3::new(0., -1., 0.));
+        assert_eq!(Some((-2., 1.)), slab);
+
+        assert!(aabb
+            .slab(Vec3::new(-5., 3., 0.), Vec3::new(1., 0., 0.))
+            .is_none());
+        assert!(aabb
+            .slab(Vec3::new(-5., 0., 0.), Vec3::new(1., 1., 0.))
+            .is_none());
+    }
+}
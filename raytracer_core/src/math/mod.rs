@@ -0,0 +1,58 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cell::RefCell;
+mod aabb;
+mod number;
+mod polynomial;
+mod quartenion;
+mod vec3;
+pub use aabb::Aabb;
+pub use number::{Number, NumberOps};
+pub use polynomial::{quadratic_roots, quartic_roots};
+pub use vec3::Vec3;
+pub use quartenion::Quaternion;
+
+// Single precision is faster, the `f64` feature removes the acne on large surfaces like
+// random_scene's ground
+#[cfg(not(feature = "f64"))]
+pub type R = f32;
+#[cfg(not(feature = "f64"))]
+pub const PI: R = std::f32::consts::PI;
+
+#[cfg(feature = "f64")]
+pub type R = f64;
+#[cfg(feature = "f64")]
+pub const PI: R = std::f64::consts::PI;
+
+pub const INFINITY: R = R::MAX;
+
+pub fn degrees_to_radians(degrees: R) -> R {
+    degrees * PI / 180.
+}
+
+pub fn random_normalized() -> R {
+    random_range(0., 1.)
+}
+
+thread_local! {
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+pub fn random_range(min: R, max: R) -> R {
+    RNG.with(|rng| rng.borrow_mut().gen_range(min, max))
+}
+
+// Restarts the numbers drawn on this thread from a fixed point, for renders that must come out the same
+pub fn seed_random(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+}
+
+pub fn clamp(n: R, min: R, max: R) -> R {
+    if n < min {
+        min
+    } else if n > max {
+        max
+    } else {
+        n
+    }
+}
@@ -4,6 +4,8 @@ pub trait NumberOps {
     fn nsqrt(&self) -> Self;
     fn fraction(numerator: i32, denominator: i32) -> Self;
     fn to_f32(&self) -> f32;
+    fn to_f64(&self) -> f64;
+    fn from_f64(x: f64) -> Self;
 
     fn from(i: i32) -> Self;
     fn pi() -> Self;
@@ -112,6 +114,14 @@ impl NumberOps for f32 {
         *self
     }
 
+    fn to_f64(&self) -> f64 {
+        *self as f64
+    }
+
+    fn from_f64(x: f64) -> Self {
+        x as f32
+    }
+
     fn from(i: i32) -> Self {
         i as Self
     }
@@ -120,7 +130,7 @@ impl NumberOps for f32 {
         let n = numerator as Self;
         let d = denominator as Self;
 
-        return n / d;
+        n / d
     }
 
     fn pi() -> Self {
@@ -131,3 +141,79 @@ impl NumberOps for f32 {
         Self::MAX
     }
 }
+
+impl NumberOps for f64 {
+    fn ncos(&self) -> Self {
+        self.cos()
+    }
+
+    fn nsin(&self) -> Self {
+        self.sin()
+    }
+
+    fn nsqrt(&self) -> Self {
+        self.sqrt()
+    }
+
+    fn to_f32(&self) -> f32 {
+        *self as f32
+    }
+
+    fn to_f64(&self) -> f64 {
+        *self
+    }
+
+    fn from_f64(x: f64) -> Self {
+        x
+    }
+
+    fn from(i: i32) -> Self {
+        i as Self
+    }
+
+    fn fraction(numerator: i32, denominator: i32) -> Self {
+        let n = numerator as Self;
+        let d = denominator as Self;
+
+        n / d
+    }
+
+    fn pi() -> Self {
+        std::f64::consts::PI
+    }
+
+    fn infinity() -> Self {
+        Self::MAX
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{INFINITY, PI, R};
+
+    fn hypot<N: Number>(a: N, b: N) -> N {
+        (a.nsqrd() + b.nsqrd()).nsqrt()
+    }
+
+    #[test]
+    fn r_follows_the_f64_feature() {
+        let size = if cfg!(feature = "f64") { 8 } else { 4 };
+
+        assert_eq!(size, std::mem::size_of::<R>());
+    }
+
+    #[test]
+    fn number_ops_agree_across_precisions() {
+        assert_eq!(5., hypot(3_f32, 4.));
+        assert_eq!(5., hypot(3_f64, 4.));
+        assert_eq!(5., hypot::<R>(3., 4.));
+
+        assert_eq!(PI, R::pi());
+        assert_eq!(INFINITY, R::infinity());
+        assert_eq!(0.25, R::fraction(1, 4));
+        assert_eq!(2., <R as NumberOps>::from(-2).nabs());
+        assert_eq!(3., <R as Number>::max(2., 3.));
+        assert!((R::from_f64(0.1).to_f64() - 0.1).abs() < R::EPSILON.to_f64());
+    }
+}
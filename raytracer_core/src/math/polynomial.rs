@@ -0,0 +1,176 @@
+use super::number::*;
+
+// Real roots of a x² + b x + c in increasing order. A double root is listed twice, so a ray that
+// grazes a surface still goes in and out of it.
+pub fn quadratic_roots<N: Number>(a: N, b: N, c: N) -> Vec<N> {
+    let zero = N::zero();
+    if a == zero {
+        return if b == zero { vec![] } else { vec![-c / b] };
+    }
+
+    let discriminant = b * b - N::from(4) * a * c;
+    if discriminant < zero {
+        return vec![];
+    }
+
+    // Adds numbers of the same sign, the textbook formula loses the small root when b is large
+    let sqrtd = discriminant.nsqrt();
+    let q = if b < zero {
+        (sqrtd - b) / N::from(2)
+    } else {
+        -(b + sqrtd) / N::from(2)
+    };
+    if q == zero {
+        return vec![zero, zero];
+    }
+
+    let (r0, r1) = (q / a, c / q);
+    if r0 <= r1 {
+        vec![r0, r1]
+    } else {
+        vec![r1, r0]
+    }
+}
+
+// Largest real root of x³ + a x² + b x + c
+fn largest_cubic_root(a: f64, b: f64, c: f64) -> f64 {
+    let q = (a * a - 3. * b) / 9.;
+    let r = (2. * a * a * a - 9. * a * b + 27. * c) / 54.;
+
+    if r * r < q * q * q {
+        // Three real roots
+        let theta = (r / (q * q * q).sqrt()).acos();
+        let sqrt_q = q.sqrt();
+        (0..3)
+            .map(|k| {
+                let angle = (theta + 2. * std::f64::consts::PI * k as f64) / 3.;
+                -2. * sqrt_q * angle.cos() - a / 3.
+            })
+            .fold(f64::MIN, f64::max)
+    } else {
+        let big = -r.signum() * (r.abs() + (r * r - q * q * q).sqrt()).cbrt();
+        let small = if big == 0. { 0. } else { q / big };
+        big + small - a / 3.
+    }
+}
+
+// Real roots of x⁴ + b x³ + c x² + d x + e in increasing order, with Ferrari's method. Always in
+// f64, f32 loses too much in the fourth powers.
+pub fn quartic_roots(b: f64, c: f64, d: f64, e: f64) -> Vec<f64> {
+    // y⁴ + p y² + q y + r with x = y - b / 4
+    let shift = b / 4.;
+    let p = c - 6. * shift * shift;
+    let q = d - 2. * c * shift + 8. * shift * shift * shift;
+    let r = e - d * shift + c * shift * shift - 3. * shift * shift * shift * shift;
+
+    // Splits it into two quadratics using a positive root of the resolvent cubic
+    let m = largest_cubic_root(p, p * p / 4. - r, -q * q / 8.);
+    let roots = if m > 1e-12 * (1. + p.abs()) {
+        let s = (2. * m).sqrt();
+        let mut roots = quadratic_roots(1., -s, p / 2. + m + q / (2. * s));
+        roots.extend(quadratic_roots(1., s, p / 2. + m - q / (2. * s)));
+        roots
+    } else {
+        // q is zero, which leaves a quadratic in y²
+        quadratic_roots(1., p, r)
+            .into_iter()
+            .filter(|z| *z >= 0.)
+            .flat_map(|z| vec![-z.sqrt(), z.sqrt()])
+            .collect()
+    };
+
+    // A couple of Newton steps on the original polynomial clean up the rounding above
+    let f = |x: f64| (((x + b) * x + c) * x + d) * x + e;
+    let df = |x: f64| ((4. * x + 3. * b) * x + 2. * c) * x + d;
+    let mut roots: Vec<_> = roots
+        .into_iter()
+        .map(|y| {
+            let mut x = y - shift;
+            for _ in 0..2 {
+                let slope = df(x);
+                if slope != 0. {
+                    x -= f(x) / slope;
+                }
+            }
+            x
+        })
+        .collect();
+    roots.sort_by(|a, b| a.total_cmp(b));
+
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_roots(expected: &[f64], actual: &[f64]) {
+        assert_eq!(expected.len(), actual.len(), "{:?}", actual);
+        for (e, a) in expected.iter().zip(actual) {
+            assert!((e - a).abs() < 1e-9, "{:?}", actual);
+        }
+    }
+
+    #[test]
+    fn quadratic_roots_are_sorted() {
+        assert_eq!(vec![-3., 2.], quadratic_roots(1., 1., -6.));
+        assert_eq!(vec![-3., 2.], quadratic_roots(-1., -1., 6.));
+        assert_eq!(vec![2., 2.], quadratic_roots(1., -4., 4.));
+        assert!(quadratic_roots(1., 0., 1.).is_empty());
+    }
+
+    #[test]
+    fn quadratic_roots_handle_lines() {
+        assert_eq!(vec![1.5], quadratic_roots(0., 2., -3.));
+        assert!(quadratic_roots(0., 0., 1.).is_empty());
+    }
+
+    #[test]
+    fn quadratic_small_root_keeps_its_precision() {
+        // x² - 1e8 x + 1 has roots close to 1e-8 and 1e8
+        let roots = quadratic_roots(1., -1e8, 1.0_f64);
+
+        assert!((roots[0] - 1e-8).abs() < 1e-20);
+    }
+
+    #[test]
+    fn largest_cubic_root_with_three_real_roots() {
+        // (x - 1)(x - 2)(x - 3)
+        assert!((largest_cubic_root(-6., 11., -6.) - 3.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn largest_cubic_root_with_one_real_root() {
+        // (x - 2)(x² + 1)
+        assert!((largest_cubic_root(-2., 1., -2.) - 2.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn quartic_with_four_roots() {
+        // (x + 6)(x - 1)(x - 2)(x - 3)
+        assert_roots(&[-6., 1., 2., 3.], &quartic_roots(0., -25., 60., -36.));
+        // (x - 1)(x - 2)(x - 3)(x - 4), not depressed
+        assert_roots(&[1., 2., 3., 4.], &quartic_roots(-10., 35., -50., 24.));
+    }
+
+    #[test]
+    fn quartic_in_x_squared() {
+        // (x² - 1)(x² - 4)
+        assert_roots(&[-2., -1., 1., 2.], &quartic_roots(0., -5., 0., 4.));
+        // (x² - 1)(x² + 1)
+        assert_roots(&[-1., 1.], &quartic_roots(0., 0., 0., -1.));
+    }
+
+    #[test]
+    fn quartic_without_real_roots() {
+        assert!(quartic_roots(0., 0., 0., 1.).is_empty());
+        // (x² + 1)(x² + 2x + 5)
+        assert!(quartic_roots(2., 6., 2., 5.).is_empty());
+    }
+
+    #[test]
+    fn quartic_with_two_roots() {
+        // (x - 1)(x + 3)(x² + 1)
+        assert_roots(&[-3., 1.], &quartic_roots(2., -2., 2., -3.));
+    }
+}
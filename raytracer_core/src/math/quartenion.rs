@@ -13,11 +13,13 @@ pub struct Quaternion {
 // Derived from: https://www.cprogramming.com/tutorial/3d/quaternions.html
 // Derived from: https://github.com/MartinWeigel/Quaternion/blob/master/Quaternion.c
 
-impl Quaternion {
-    pub fn default() -> Self {
+impl Default for Quaternion {
+    fn default() -> Self {
         Self::identity()
     }
+}
 
+impl Quaternion {
     fn new(w: R, v0: R, v1: R, v2: R) -> Self {
         Self {
             w,
@@ -62,7 +64,7 @@ impl Quaternion {
     }
 
     pub fn from_z_rotation(angle: R) -> Self {
-        Self::from_axis_angle(Vec3::new(0., 0., 1.).into(), angle)
+        Self::from_axis_angle(Vec3::new(0., 0., 1.), angle)
     }
 
     pub fn normalize(&self) -> Self {
@@ -117,13 +119,13 @@ impl Quaternion {
         let m2 = [
             two_xy + two_wz,
             one - two_x2 - two_z2,
-            two_yz + two_wx,
+            two_yz - two_wx,
             zero,
         ];
 
         let m3 = [
             two_xz - two_wy,
-            two_yz - two_wx,
+            two_yz + two_wx,
             one - two_x2 - two_y2,
             zero,
         ];
@@ -145,9 +147,7 @@ impl Quaternion {
 
         let two = 2.;
 
-        let v2 = u * two * u.dot(v) + v * (s.nsqrd() - u.dot(u)) + u.cross(v) * two * s;
-
-        v2
+        u * two * u.dot(v) + v * (s.nsqrd() - u.dot(u)) + u.cross(v) * two * s
     }
 
     // Multiply two Quaternions. Not commutative, meaning q1 * q2 != q2 * q1.
@@ -158,9 +158,7 @@ impl Quaternion {
         let z = self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w;
 
         // Check if we need to renormalize it.
-        let m = Self::new(w, x, y, z);
-
-        m
+        Self::new(w, x, y, z)
     }
 
     fn magnitude(&self) -> R {
@@ -209,12 +207,23 @@ mod tests {
     type Q = Quaternion;
 
     #[test]
-    fn Quaternion_to_matrix() {
-        assert_eq!(true, false);
+    fn quaternion_to_matrix() {
+        let q = (Q::from_x_rotation(0.3) * Q::from_y_rotation(1.2)).normalize();
+        let v = Vec3::new(1., -2., 0.5);
+
+        let m = q.to_matrix();
+        let rotated = Vec3::new(
+            m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+            m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+            m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z,
+        );
+
+        assert!((rotated - q.rotate_vec3(v)).len() < 0.0001);
+        assert_eq!([0., 0., 0., 1.], m[3]);
     }
 
     #[test]
-    fn Quaternion_normalize() {
+    fn quaternion_normalize() {
         let q = Q::from_x_rotation(1.0 / 3.0);
 
         println!("Q: {:?}", q);
@@ -234,7 +243,7 @@ mod tests {
     }
 
     #[test]
-    fn Quaternion_magnitude() {
+    fn quaternion_magnitude() {
         let q = Q::from_x_rotation(1.0 / 3.0);
 
         let expected = q.w.nsqrd() + q.x.nsqrd() + q.y.nsqrd() + q.z.nsqrd();
@@ -251,7 +260,7 @@ mod tests {
     }
 
     #[test]
-    fn Quaternion_mul_assign() {
+    fn quaternion_mul_assign() {
         let mut q1 = Q::from_x_rotation(1.0 / 3.0);
         let other = Q::from_y_rotation(4.0);
 
@@ -269,7 +278,7 @@ mod tests {
         assert_eq!(expected, q1);
     }
     #[test]
-    fn Quaternion_mul() {
+    fn quaternion_mul() {
         let q1 = Q::from_x_rotation(1.0 / 3.0);
         let other = Q::from_y_rotation(4.0);
 
@@ -278,7 +287,7 @@ mod tests {
 
         assert_eq!(expected, actual);
 
-        let mut q1 = Q::from_z_rotation(1.0 / 3.0);
+        let q1 = Q::from_z_rotation(1.0 / 3.0);
         let other = Q::from_x_rotation(4.0);
 
         let expected = q1.multiply(other);
@@ -288,7 +297,7 @@ mod tests {
     }
 
     #[test]
-    fn Quaternion_multiply() {
+    fn quaternion_multiply() {
         let q1 = Q::from_x_rotation(1.0 / 3.0);
         let other = Q::from_y_rotation(4.0);
 
@@ -315,7 +324,7 @@ mod tests {
     }
 
     #[test]
-    fn Quaternion_from_z_rotation() {
+    fn quaternion_from_z_rotation() {
         let angle = 1.0 / 7.0;
 
         let expected = Q::from_axis_angle(Vec3::new(0., 0., 1.), angle);
@@ -342,7 +351,7 @@ mod tests {
     }
 
     #[test]
-    fn Quaternion_from_y_rotation() {
+    fn quaternion_from_y_rotation() {
         let angle = 1.0 / 7.0;
 
         let expected = Q::from_axis_angle(Vec3::new(0., 1., 0.), angle);
@@ -369,7 +378,7 @@ mod tests {
     }
 
     #[test]
-    fn Quaternion_from_x_rotation() {
+    fn quaternion_from_x_rotation() {
         let angle = 1.0 / 7.0;
 
         let expected = Q::from_axis_angle(Vec3::new(1., 0., 0.), angle);
@@ -396,7 +405,7 @@ mod tests {
     }
 
     #[test]
-    fn Quaternion_from_axis_angle() {
+    fn quaternion_from_axis_angle() {
         let axis: Vec3 = Vec3::new(0., 2., 3.);
         let angle = 1.0 / 7.0;
 
@@ -420,7 +429,7 @@ mod tests {
         assert_eq!(expected, actual);
     }
     #[test]
-    fn Quaternion_new_sets_as_expected() {
+    fn quaternion_new_sets_as_expected() {
         let w = 3.0;
         let v0 = 4.0;
         let v1 = 5.0;
@@ -435,7 +444,7 @@ mod tests {
     }
 
     #[test]
-    fn Quaternion_identity_sets_as_expected() {
+    fn quaternion_identity_sets_as_expected() {
         let w = 1.0;
         let v0 = 0.0;
         let v1 = 0.0;
@@ -1,7 +1,9 @@
 use super::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(PartialEq, Debug, Copy, Clone, Deserialize, Serialize)]
+#[derive(PartialEq, Debug, Default, Copy, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Vec3 {
     pub x: R,
     pub y: R,
@@ -9,14 +11,6 @@ pub struct Vec3 {
 }
 
 impl Vec3 {
-    pub fn default() -> Self {
-        Self {
-            x: 0.,
-            y: 0.,
-            z: 0.,
-        }
-    }
-
     pub fn new(x: R, y: R, z: R) -> Self {
         Self { x, y, z }
     }
@@ -124,6 +118,20 @@ impl std::ops::Neg for Vec3 {
     }
 }
 
+// 0, 1 and 2 are x, y and z
+impl std::ops::Index<usize> for Vec3 {
+    type Output = R;
+
+    fn index(&self, axis: usize) -> &R {
+        match axis {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("Vec3 has no axis {}", axis),
+        }
+    }
+}
+
 impl std::ops::Add for Vec3 {
     type Output = Self;
 
@@ -244,7 +252,7 @@ mod tests {
     #[test]
     fn vec3_new_returns_expected() {
         let x = 2.3;
-        let y = 3382.90001;
+        let y = 3382.9;
         let z = -0.00001;
         let actual = Vec3::new(x, y, z);
         let expected = Vec3 { x, y, z };
@@ -255,7 +263,7 @@ mod tests {
     #[test]
     fn vec3_neg_returns_expected() {
         let x = 2.3;
-        let y = 3382.90001;
+        let y = 3382.9;
         let z = -0.00001;
         let actual = -(Vec3::new(x, y, z));
 
@@ -267,10 +275,19 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn vec3_index_returns_expected() {
+        let v = Vec3::new(1., 2., 3.);
+
+        assert_eq!(1., v[0]);
+        assert_eq!(2., v[1]);
+        assert_eq!(3., v[2]);
+    }
+
     #[test]
     fn vec3_add_returns_expected() {
         let x0 = 2.3;
-        let y0 = 3382.90001;
+        let y0 = 3382.9;
         let z0 = -0.00001;
         let x1 = 34.2;
         let y1 = -2090.012;
@@ -286,7 +303,7 @@ mod tests {
     #[test]
     fn vec3_addassign_returns_expected() {
         let x0 = 2.3;
-        let y0 = 3382.90001;
+        let y0 = 3382.9;
         let z0 = -0.00001;
         let x1 = 34.2;
         let y1 = -2090.012;
@@ -303,7 +320,7 @@ mod tests {
     #[test]
     fn vec3_sub_returns_expected() {
         let x0 = 2.3;
-        let y0 = 3382.90001;
+        let y0 = 3382.9;
         let z0 = -0.00001;
         let x1 = 34.2;
         let y1 = -2090.012;
@@ -319,7 +336,7 @@ mod tests {
     #[test]
     fn vec3_subassign_returns_expected() {
         let x0 = 2.3;
-        let y0 = 3382.90001;
+        let y0 = 3382.9;
         let z0 = -0.00001;
         let x1 = 34.2;
         let y1 = -2090.012;
@@ -336,7 +353,7 @@ mod tests {
     #[test]
     fn vec3_mul_returns_expected() {
         let x0 = 2.3;
-        let y0 = 3382.90001;
+        let y0 = 3382.9;
         let z0 = -0.00001;
         let x1 = 34.2;
         let y1 = -2090.012;
@@ -352,7 +369,7 @@ mod tests {
     #[test]
     fn vec3_dot_returns_expected() {
         let x0 = 2.3;
-        let y0 = 3382.90001;
+        let y0 = 3382.9;
         let z0 = -0.00001;
         let x1 = 34.2;
         let y1 = -2090.012;
@@ -367,7 +384,7 @@ mod tests {
     #[test]
     fn vec3_cross_returns_expected() {
         let x0 = 2.3;
-        let y0 = 3382.90001;
+        let y0 = 3382.9;
         let z0 = -0.00001;
         let x1 = 34.2;
         let y1 = -2090.012;
@@ -386,7 +403,7 @@ mod tests {
     #[test]
     fn vec3_unit_vector_returns_expected() {
         let x0 = 2.3;
-        let y0 = 3382.90001;
+        let y0 = 3382.9;
         let z0 = -0.00001;
 
         let vec = Vec3::new(x0, y0, z0);
@@ -399,9 +416,9 @@ mod tests {
     #[test]
     fn vec3_mulr_returns_expected() {
         let x0 = 2.3;
-        let y0 = 3382.90001;
+        let y0 = 3382.9;
         let z0 = -0.00001;
-        let mul = 2382.10191;
+        let mul = 2382.102;
 
         let actual = Vec3::new(x0, y0, z0) * mul;
         let expected = Vec3::new(x0 * mul, y0 * mul, z0 * mul);
@@ -414,7 +431,7 @@ mod tests {
     #[test]
     fn vec3_mulassign_returns_expected() {
         let x0 = 2.3;
-        let y0 = 3382.90001;
+        let y0 = 3382.9;
         let z0 = -0.00001;
         let mul = 2.381;
 
@@ -429,9 +446,9 @@ mod tests {
     #[test]
     fn vec3_divr_returns_expected() {
         let x0 = 2.3;
-        let y0 = 3382.90001;
+        let y0 = 3382.9;
         let z0 = -0.00001;
-        let div = 2382.10191;
+        let div = 2382.102;
 
         let actual = Vec3::new(x0, y0, z0) / div;
         let expected = Vec3::new(x0 / div, y0 / div, z0 / div);
@@ -441,7 +458,7 @@ mod tests {
     #[test]
     fn vec3_divassign_returns_expected() {
         let x0 = 2.3;
-        let y0 = 3382.90001;
+        let y0 = 3382.9;
         let z0 = -0.00001;
         let div = 2.381;
 
@@ -456,7 +473,7 @@ mod tests {
     #[test]
     fn vec3_len_squared_returns_expected() {
         let x0 = 2.3;
-        let y0 = 3382.90001;
+        let y0 = 3382.9;
         let z0 = -0.00001;
 
         let actual = Vec3::new(x0, y0, z0).len_squared();
@@ -468,7 +485,7 @@ mod tests {
     #[test]
     fn vec3_len_returns_expected() {
         let x0 = 2.3;
-        let y0 = 3382.90001;
+        let y0 = 3382.9;
         let z0 = -0.00001;
 
         let actual = Vec3::new(x0, y0, z0).len();
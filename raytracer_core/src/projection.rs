@@ -0,0 +1,153 @@
+use super::math::{degrees_to_radians, Vec3, R};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+pub enum ProjectionPreset {
+    // True isometric, the three axes are 120 degrees apart and floor tiles are sqrt(3):1
+    Isometric,
+    // The 2:1 projection most "isometric" pixel art uses, tile edges rise one pixel every two
+    Dimetric,
+    // Looking straight down, with -z at the top of the image
+    TopDown,
+    // Looking along -z, with y up
+    SideView,
+}
+
+// Orthographic game projection. Overrides the camera's eye direction, up_dir and perspective, and
+// sizes the view so a floor tile is drawn exactly tile_width output pixels wide.
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct Projection {
+    pub preset: ProjectionPreset,
+    // Size of a floor tile in world units
+    pub tile_size: R,
+    pub tile_width: u32,
+    // Turns the view around the y axis in degrees. Multiples of 90 keep the tile footprint.
+    pub rotation: R,
+}
+
+impl Default for Projection {
+    fn default() -> Self {
+        Self {
+            preset: ProjectionPreset::Dimetric,
+            tile_size: 1.,
+            tile_width: 32,
+            rotation: 0.,
+        }
+    }
+}
+
+impl Projection {
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = vec![];
+
+        if self.tile_size <= 0. {
+            errors.push(format!(
+                "tile_size must be positive, got {}",
+                self.tile_size
+            ));
+        }
+        if self.tile_width == 0 {
+            errors.push("tile_width must be at least 1".to_string());
+        }
+
+        errors
+    }
+
+    // Direction from the target towards the eye, and the up direction of the image
+    pub fn view(&self) -> (Vec3, Vec3) {
+        let y = Vec3::new(0., 1., 0.);
+
+        let elevation = match self.preset {
+            // Tilted until a cube's top, front and side faces all have the same projected area
+            ProjectionPreset::Isometric => (1. / (2. as R).sqrt()).atan(),
+            // sin(30) = 1/2 squashes the tile diagonal to half its width
+            ProjectionPreset::Dimetric => degrees_to_radians(30.),
+            ProjectionPreset::TopDown => return (y, -self.horizontal(0.)),
+            ProjectionPreset::SideView => 0.,
+        };
+        let azimuth = match self.preset {
+            ProjectionPreset::Isometric | ProjectionPreset::Dimetric => 45.,
+            _ => 0.,
+        };
+
+        let dir = self.horizontal(azimuth) * elevation.cos() + y * elevation.sin();
+        (dir, y)
+    }
+
+    // Output pixels per world unit along the horizontal axis of the image
+    pub fn pixels_per_unit(&self) -> R {
+        let footprint = match self.preset {
+            // The tile is seen corner on, so its diagonal spans the image horizontally
+            ProjectionPreset::Isometric | ProjectionPreset::Dimetric => {
+                self.tile_size * (2. as R).sqrt()
+            }
+            ProjectionPreset::TopDown | ProjectionPreset::SideView => self.tile_size,
+        };
+
+        self.tile_width as R / footprint
+    }
+
+    // Unit vector in the xz plane, azimuth degrees from +z towards +x, plus the rotation
+    fn horizontal(&self, azimuth: R) -> Vec3 {
+        let azimuth = degrees_to_radians(azimuth + self.rotation);
+        Vec3::new(azimuth.sin(), 0., azimuth.cos())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn projection(preset: ProjectionPreset) -> Projection {
+        Projection {
+            preset,
+            ..Projection::default()
+        }
+    }
+
+    #[test]
+    fn views_are_unit_length_and_orthogonal_to_up() {
+        let presets = [
+            ProjectionPreset::Isometric,
+            ProjectionPreset::Dimetric,
+            ProjectionPreset::TopDown,
+            ProjectionPreset::SideView,
+        ];
+
+        for preset in &presets {
+            let (dir, up) = projection(*preset).view();
+            assert!((dir.len() - 1.).abs() < 1e-6, "{:?}", preset);
+            assert!(dir.cross(up).len() > 0.5, "{:?}", preset);
+        }
+    }
+
+    #[test]
+    fn isometric_view_is_along_cube_diagonal() {
+        let (dir, _) = projection(ProjectionPreset::Isometric).view();
+        let diagonal = Vec3::new(1., 1., 1.).unit_vector();
+
+        assert!((dir - diagonal).len() < 1e-6);
+    }
+
+    #[test]
+    fn rotation_turns_view_around_y() {
+        let rotated = Projection {
+            rotation: 90.,
+            ..projection(ProjectionPreset::SideView)
+        };
+        let (dir, up) = rotated.view();
+
+        assert!((dir - Vec3::new(1., 0., 0.)).len() < 1e-6);
+        assert_eq!(Vec3::new(0., 1., 0.), up);
+    }
+
+    #[test]
+    fn top_down_has_negative_z_up() {
+        let (dir, up) = projection(ProjectionPreset::TopDown).view();
+
+        assert_eq!(Vec3::new(0., 1., 0.), dir);
+        assert!((up - Vec3::new(0., 0., -1.)).len() < 1e-6);
+    }
+}
@@ -0,0 +1,37 @@
+use super::math::{Vec3, R};
+
+#[derive(Copy, Clone, Debug)]
+pub struct Ray {
+    origin: Vec3,
+    direction: Vec3,
+}
+
+impl Ray {
+    pub fn new(origin: Vec3, direction: Vec3) -> Self {
+        Self { origin, direction }
+    }
+
+    pub fn origin(&self) -> Vec3 {
+        self.origin
+    }
+
+    pub fn direction(&self) -> Vec3 {
+        self.direction
+    }
+
+    pub fn at(&self, t: R) -> Vec3 {
+        self.origin + t * self.direction
+    }
+
+    // Moves the origin off the surface, to the side the ray is heading, so it doesn't hit the surface it started on
+    pub fn offset(&self, normal: Vec3) -> Self {
+        let bias = 0.001;
+        let normal = if self.direction.dot(normal) < 0. {
+            -normal
+        } else {
+            normal
+        };
+
+        Self::new(self.origin + normal * bias, self.direction)
+    }
+}
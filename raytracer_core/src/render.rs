@@ -0,0 +1,355 @@
+//! Renders a [`Scene`] into a [`Framebuffer`], for tools that embed the raytracer.
+
+use super::camera::Camera;
+use super::color::Color;
+use super::hittable::objects::World;
+use super::lighting::LightingSettings;
+use super::math::{random_normalized, R};
+use super::ray::Ray;
+use image::{ImageBuffer, Rgba, RgbaImage};
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+/// Turns a point on the image into a ray. `(0, 0)` is the bottom left corner and `(1, 1)` the top right.
+pub trait RayCaster: Sync {
+    fn ray(&self, u: R, v: R) -> Ray;
+}
+
+impl RayCaster for Camera {
+    fn ray(&self, u: R, v: R) -> Ray {
+        Camera::ray(self, u, v)
+    }
+}
+
+/// Everything that ends up in the image. The lighting holds the lights, the sky and the bounce depth.
+/// Only borrowed, so one world can be rendered through several cameras.
+pub struct Scene<'a> {
+    pub camera: &'a dyn RayCaster,
+    pub world: &'a World,
+    pub lighting: &'a LightingSettings,
+}
+
+/// How many rows are finished out of the total, passed to [`RenderOptions::progress`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Progress {
+    pub rows_done: u32,
+    pub rows: u32,
+}
+
+impl Progress {
+    pub fn fraction(&self) -> R {
+        self.rows_done as R / self.rows as R
+    }
+}
+
+pub struct RenderOptions<'a> {
+    pub width: u32,
+    pub height: u32,
+    /// Rays per pixel. A single sample goes through the pixel center, more are spread randomly over the pixel.
+    pub samples_per_pixel: u32,
+    /// Called from the worker threads each time a row is finished.
+    pub progress: Option<&'a (dyn Fn(Progress) + Sync)>,
+    /// Checked before each row, setting it stops the render and leaves the remaining rows transparent.
+    pub cancel: Option<&'a AtomicBool>,
+}
+
+impl<'a> RenderOptions<'a> {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            samples_per_pixel: 1,
+            progress: None,
+            cancel: None,
+        }
+    }
+
+    pub fn aspect_ratio(&self) -> R {
+        self.width as R / self.height as R
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel
+            .map(|cancel| cancel.load(Ordering::Relaxed))
+            .unwrap_or(false)
+    }
+}
+
+/// Linear colors, row by row from the top left corner.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Framebuffer {
+    width: u32,
+    height: u32,
+    pixels: Vec<Color>,
+    complete: bool,
+}
+
+impl Framebuffer {
+    /// A transparent black image.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![Color::new(0., 0., 0., 0.); (width * height) as usize],
+            complete: true,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> Color {
+        self.pixels[self.index(x, y)]
+    }
+
+    pub fn set(&mut self, x: u32, y: u32, color: Color) {
+        let i = self.index(x, y);
+        self.pixels[i] = color;
+    }
+
+    /// False if the render was cancelled before every row was drawn.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    pub fn to_image(&self) -> RgbaImage {
+        ImageBuffer::from_fn(self.width, self.height, |x, y| Rgba(self.get(x, y).into()))
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        assert!(x < self.width && y < self.height, "pixel out of bounds");
+        (y * self.width + x) as usize
+    }
+}
+
+impl From<Framebuffer> for RgbaImage {
+    fn from(framebuffer: Framebuffer) -> Self {
+        framebuffer.to_image()
+    }
+}
+
+/// Renders the rows in parallel. Pixels where the rays escape are transparent unless the scene has a sky.
+pub fn render(scene: &Scene, options: &RenderOptions) -> Framebuffer {
+    let rows_done = AtomicU32::new(0);
+
+    let rows: Vec<Option<Vec<Color>>> = (0..options.height)
+        .into_par_iter()
+        .map(|y| {
+            if options.cancelled() {
+                return None;
+            }
+
+            let row = (0..options.width)
+                .map(|x| sample_pixel(scene, options, x, y))
+                .collect();
+
+            let done = rows_done.fetch_add(1, Ordering::Relaxed) + 1;
+            if let Some(progress) = options.progress {
+                progress(Progress {
+                    rows_done: done,
+                    rows: options.height,
+                });
+            }
+
+            Some(row)
+        })
+        .collect();
+
+    let mut framebuffer = Framebuffer::new(options.width, options.height);
+    for (y, row) in rows.into_iter().enumerate() {
+        match row {
+            Some(row) => {
+                for (x, color) in row.into_iter().enumerate() {
+                    framebuffer.set(x as u32, y as u32, color);
+                }
+            }
+            None => framebuffer.complete = false,
+        }
+    }
+
+    framebuffer
+}
+
+fn sample_pixel(scene: &Scene, options: &RenderOptions, x: u32, y: u32) -> Color {
+    let samples = options.samples_per_pixel.max(1);
+    // Rows go top down in the image, but v goes up
+    let j = options.height - 1 - y;
+
+    let mut color = Color::new(0., 0., 0., 0.);
+    for _ in 0..samples {
+        let (du, dv) = if samples == 1 {
+            (0.5, 0.5)
+        } else {
+            (random_normalized(), random_normalized())
+        };
+
+        let u = (x as R + du) / options.width as R;
+        let v = (j as R + dv) / options.height as R;
+
+        let ray = scene.camera.ray(u, v);
+        color += scene.lighting.radiance(&ray, scene.world);
+    }
+
+    color.from_samples(samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::CameraSettings;
+    use crate::hittable::{materials::Material, objects::Sphere};
+    use crate::lighting::Sky;
+    use crate::math::Vec3;
+    use std::sync::Mutex;
+
+    fn camera() -> Camera {
+        CameraSettings::default().into_camera(1.)
+    }
+
+    fn sphere_world() -> World {
+        let mut world = World::new();
+        world.add_item(Sphere::new(
+            Vec3::new(0., 0., 0.),
+            1.,
+            Material::Lambertian {
+                albedo: Color::new(1., 0., 0., 1.),
+            },
+        ));
+        world
+    }
+
+    fn lighting(sky: Option<Sky>) -> LightingSettings {
+        LightingSettings {
+            sky,
+            ..LightingSettings::default()
+        }
+    }
+
+    fn render_sphere(sky: Option<Sky>, options: &RenderOptions) -> Framebuffer {
+        let scene = Scene {
+            camera: &camera(),
+            world: &sphere_world(),
+            lighting: &lighting(sky),
+        };
+
+        render(&scene, options)
+    }
+
+    #[test]
+    fn renders_sphere_over_transparent_background() {
+        let framebuffer = render_sphere(None, &RenderOptions::new(9, 9));
+
+        assert!(framebuffer.is_complete());
+        assert_eq!(1., framebuffer.get(4, 4).a);
+        assert!(framebuffer.get(4, 4).r > 0.);
+        assert_eq!(Color::new(0., 0., 0., 0.), framebuffer.get(0, 0));
+    }
+
+    #[test]
+    fn escaped_rays_see_the_sky() {
+        let white = Color::new(1., 1., 1., 1.);
+        let sky = Sky {
+            top: white,
+            bottom: white,
+        };
+
+        let framebuffer = render_sphere(Some(sky), &RenderOptions::new(9, 9));
+
+        assert_eq!(white, framebuffer.get(0, 0));
+    }
+
+    #[test]
+    fn first_row_is_the_top_of_the_image() {
+        let mut world = World::new();
+        // Only covers the upper half of the view
+        world.add_item(Sphere::new(
+            Vec3::new(0., 1., 0.),
+            0.5,
+            Material::Lambertian {
+                albedo: Color::new(1., 1., 1., 1.),
+            },
+        ));
+        let scene = Scene {
+            camera: &camera(),
+            world: &world,
+            lighting: &lighting(None),
+        };
+
+        let image = render(&scene, &RenderOptions::new(9, 9)).to_image();
+
+        assert_eq!(255, image.get_pixel(4, 2)[3]);
+        assert_eq!(0, image.get_pixel(4, 6)[3]);
+    }
+
+    #[test]
+    fn converts_to_rgba_image() {
+        let mut framebuffer = Framebuffer::new(3, 2);
+        framebuffer.set(2, 1, Color::new(1., 0., 0.5, 1.));
+
+        let image: RgbaImage = framebuffer.into();
+
+        assert_eq!((3, 2), image.dimensions());
+        assert_eq!(&Rgba([255, 0, 127, 255]), image.get_pixel(2, 1));
+        assert_eq!(&Rgba([0, 0, 0, 0]), image.get_pixel(0, 0));
+    }
+
+    #[test]
+    fn reports_progress_for_every_row() {
+        let reports = Mutex::new(vec![]);
+        let progress = |p: Progress| reports.lock().unwrap().push(p);
+        let options = RenderOptions {
+            progress: Some(&progress),
+            ..RenderOptions::new(4, 6)
+        };
+
+        render_sphere(None, &options);
+
+        let mut done: Vec<_> = reports
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|p| p.rows_done)
+            .collect();
+        done.sort_unstable();
+        assert_eq!(vec![1, 2, 3, 4, 5, 6], done);
+        assert!(reports.lock().unwrap().iter().all(|p| p.rows == 6));
+    }
+
+    #[test]
+    fn cancelled_render_is_incomplete() {
+        let cancel = AtomicBool::new(true);
+        let options = RenderOptions {
+            cancel: Some(&cancel),
+            ..RenderOptions::new(9, 9)
+        };
+
+        let framebuffer = render_sphere(None, &options);
+
+        assert!(!framebuffer.is_complete());
+        assert_eq!(Color::new(0., 0., 0., 0.), framebuffer.get(4, 4));
+    }
+
+    #[test]
+    fn cancelling_from_progress_stops_the_render() {
+        let cancel = AtomicBool::new(false);
+        let progress = |_: Progress| cancel.store(true, Ordering::Relaxed);
+        let options = RenderOptions {
+            progress: Some(&progress),
+            cancel: Some(&cancel),
+            ..RenderOptions::new(4, 4096)
+        };
+
+        let framebuffer = render_sphere(None, &options);
+
+        assert!(!framebuffer.is_complete());
+    }
+}
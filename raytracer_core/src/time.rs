@@ -1,9 +1,15 @@
-use std::time::{Duration, Instant, SystemTime};
+use std::time::{Duration, Instant};
 
 pub struct Clock {
     start: Instant,
 }
 
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Clock {
     pub fn new() -> Self {
         Self {
@@ -12,7 +18,7 @@ impl Clock {
     }
 
     pub fn elapsed(&self) -> Duration {
-        return Instant::now() - self.start;
+        Instant::now() - self.start
     }
 
     pub fn stop_watch(&mut self) -> Duration {
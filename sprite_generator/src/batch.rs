@@ -0,0 +1,327 @@
+use crate::cli::{from_cfg_file, load_cfg, CfgError, RenderSettings};
+use crate::renderer::{RenderError, ShadingModes, SpriteRenderer};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Renders every mesh in the list with the same config. Relative paths are resolved against the
+// directory the manifest is in.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Manifest {
+    pub config: PathBuf,
+    pub meshes: Vec<PathBuf>,
+}
+
+pub struct Job {
+    pub config: PathBuf,
+    pub settings: RenderSettings,
+}
+
+impl Job {
+    pub fn new(config: &Path, settings: RenderSettings) -> Self {
+        Self {
+            config: config.into(),
+            settings,
+        }
+    }
+}
+
+pub struct Output {
+    pub dir: PathBuf,
+    // File name template, `{config}`, `{mesh}` and `{pass}` are replaced by the file stems
+    pub name: String,
+}
+
+impl Output {
+    pub fn path(&self, job: &Job, pass: ShadingModes) -> Result<PathBuf, BatchError> {
+        let name = self
+            .name
+            .replace("{config}", &file_stem(&job.config))
+            .replace("{mesh}", &file_stem(Path::new(&job.settings.mesh_file)))
+            .replace("{pass}", pass.name());
+
+        if name.contains('{') || name.contains('}') {
+            return Err(BatchError::Template(self.name.clone()));
+        }
+
+        Ok(self.dir.join(name))
+    }
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[derive(Debug)]
+pub enum BatchError {
+    Cfg(CfgError),
+    Template(String),
+    DuplicateOutput(PathBuf),
+    Render(PathBuf, RenderError),
+    Save(PathBuf, image::ImageError),
+    CreateDir(PathBuf, std::io::Error),
+}
+
+impl std::fmt::Display for BatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BatchError::Cfg(e) => e.fmt(f),
+            BatchError::Template(name) => write!(
+                f,
+                "Unknown placeholder in '{}', expected {{config}}, {{mesh}} or {{pass}}",
+                name
+            ),
+            BatchError::DuplicateOutput(path) => write!(
+                f,
+                "More than one pass would be written to '{}', add {{config}} or {{mesh}} to the output name",
+                path.display()
+            ),
+            BatchError::Render(config, e) => {
+                write!(f, "Unable to render '{}': {}", config.display(), e)
+            }
+            BatchError::Save(path, e) => write!(f, "Unable to save '{}': {}", path.display(), e),
+            BatchError::CreateDir(path, e) => {
+                write!(f, "Unable to create '{}': {}", path.display(), e)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+impl From<CfgError> for BatchError {
+    fn from(e: CfgError) -> Self {
+        BatchError::Cfg(e)
+    }
+}
+
+pub fn load_jobs(configs: &[PathBuf], manifests: &[PathBuf]) -> Result<Vec<Job>, BatchError> {
+    let mut jobs = vec![];
+
+    for config in configs {
+        jobs.push(Job::new(config, load_cfg(config)?));
+    }
+
+    for manifest_file in manifests {
+        let manifest: Manifest = from_cfg_file(manifest_file)?;
+        let dir = manifest_file.parent().unwrap_or_else(|| Path::new(""));
+
+        let config = dir.join(&manifest.config);
+        let settings = load_cfg(&config)?;
+
+        for mesh in &manifest.meshes {
+            let mut settings = settings.clone();
+            settings.mesh_file = dir.join(mesh).to_string_lossy().into_owned();
+            jobs.push(Job::new(&config, settings));
+        }
+    }
+
+    Ok(jobs)
+}
+
+// Fails before anything is rendered if the template is invalid or passes would overwrite each other
+pub fn check_outputs(jobs: &[Job], output: &Output) -> Result<(), BatchError> {
+    let mut paths = HashSet::new();
+
+    for job in jobs {
+        for pass in &job.settings.passes {
+            let path = output.path(job, *pass)?;
+            if !paths.insert(path.clone()) {
+                return Err(BatchError::DuplicateOutput(path));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn render_job(job: &Job, output: &Output) -> Result<(), BatchError> {
+    let cfg = &job.settings;
+
+    let mut renderer = SpriteRenderer {
+        image_width: cfg.image_width,
+        image_height: cfg.image_height,
+        shading_mode: ShadingModes::Normal,
+        camera_settings: cfg.camera_settings,
+        mesh_file: cfg.mesh_file.clone(),
+        blacken_normal_map: cfg.blacken_normal_map,
+        normal_map: cfg.normal_map,
+        ambient_occlusion: cfg.ambient_occlusion,
+        lighting: cfg.lighting.clone(),
+        sampling: cfg.sampling.clone(),
+        pixel_art: cfg.pixel_art.clone(),
+        outline: cfg.outline,
+        toon: cfg.toon,
+    };
+
+    for pass in &cfg.passes {
+        renderer.shading_mode = *pass;
+
+        let img = renderer
+            .render()
+            .map_err(|e| BatchError::Render(job.config.clone(), e))?;
+
+        let path = output.path(job, *pass)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| BatchError::CreateDir(dir.into(), e))?;
+        }
+        img.save(&path).map_err(|e| BatchError::Save(path, e))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{self, TempDir};
+
+    fn temp_dir(name: &str) -> TempDir {
+        test_util::temp_dir(&format!("batch_{}", name))
+    }
+
+    fn write_cfg(path: &Path, mesh_file: &str, passes: &str) {
+        let cfg = format!(
+            r#"{{
+    "camera_settings": {{
+        "perspective": {{ "Perspective": {{ "scale": 1 }} }},
+        "eye": {{ "x": 0.0, "y": 0.0, "z": 12.0 }},
+        "target": {{ "x": 0.0, "y": 0.0, "z": 0.0 }},
+        "up_dir": {{ "x": 0.0, "y": 1.0, "z": 0.0 }},
+        "v_fov": 30.0,
+        "focal_len": 1
+    }},
+    "image_width": 8,
+    "image_height": 8,
+    "mesh_file": {:?},
+    "blacken_normal_map": false,
+    "passes": {}
+}}"#,
+            mesh_file, passes
+        );
+        fs::write(path, cfg).unwrap();
+    }
+
+    fn cubes_path() -> String {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("res/cubes.obj")
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn output(dir: &Path, name: &str) -> Output {
+        Output {
+            dir: dir.into(),
+            name: name.into(),
+        }
+    }
+
+    #[test]
+    fn expands_name_template() {
+        let dir = temp_dir("template");
+        let cfg = dir.join("hero.json");
+        write_cfg(&cfg, "res/knight.obj", r#"["Normal"]"#);
+        let jobs = load_jobs(&[cfg], &[]).unwrap();
+
+        let path = output(Path::new("out"), "{config}/{mesh}_{pass}.png")
+            .path(&jobs[0], ShadingModes::AmbientOcclusion)
+            .unwrap();
+
+        assert_eq!(path, Path::new("out/hero/knight_ao.png"));
+    }
+
+    #[test]
+    fn rejects_unknown_placeholder() {
+        let dir = temp_dir("placeholder");
+        let cfg = dir.join("cfg.json");
+        write_cfg(&cfg, "cubes.obj", r#"["Normal"]"#);
+        let jobs = load_jobs(&[cfg], &[]).unwrap();
+
+        match check_outputs(&jobs, &output(&dir, "{frame}_{pass}.png")) {
+            Err(BatchError::Template(_)) => {}
+            _ => panic!("expected a template error"),
+        }
+    }
+
+    #[test]
+    fn rejects_outputs_that_overwrite_each_other() {
+        let dir = temp_dir("duplicate");
+        let manifest = dir.join("manifest.json");
+        write_cfg(&dir.join("cfg.json"), "base.obj", r#"["Normal"]"#);
+        fs::write(
+            &manifest,
+            r#"{ "config": "cfg.json", "meshes": ["a.obj", "b.obj"] }"#,
+        )
+        .unwrap();
+        let jobs = load_jobs(&[], &[manifest]).unwrap();
+
+        match check_outputs(&jobs, &output(&dir, "{pass}.png")) {
+            Err(BatchError::DuplicateOutput(path)) => assert_eq!(path, dir.join("normal.png")),
+            _ => panic!("expected a duplicate output error"),
+        }
+        assert!(check_outputs(&jobs, &output(&dir, "{mesh}_{pass}.png")).is_ok());
+    }
+
+    #[test]
+    fn manifest_paths_are_relative_to_manifest() {
+        let dir = temp_dir("manifest");
+        let manifest = dir.join("manifest.json");
+        write_cfg(&dir.join("cfg.json"), "base.obj", r#"["Normal"]"#);
+        fs::write(
+            &manifest,
+            r#"{ "config": "cfg.json", "meshes": ["meshes/a.obj", "meshes/b.obj"] }"#,
+        )
+        .unwrap();
+
+        let jobs = load_jobs(&[], &[manifest]).unwrap();
+        let meshes: Vec<_> = jobs.iter().map(|j| j.settings.mesh_file.clone()).collect();
+
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[0].config, dir.join("cfg.json"));
+        assert_eq!(
+            meshes,
+            vec![
+                dir.join("meshes/a.obj").to_string_lossy(),
+                dir.join("meshes/b.obj").to_string_lossy()
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_passes_into_output_dir() {
+        let dir = temp_dir("render");
+        let cfg = dir.join("cubes.json");
+        write_cfg(&cfg, &cubes_path(), r#"["Normal", "Mask"]"#);
+        let jobs = load_jobs(&[cfg], &[]).unwrap();
+        let out = output(&dir.join("out"), "{mesh}_{pass}.png");
+
+        render_job(&jobs[0], &out).unwrap();
+
+        let normal = image::open(dir.join("out/cubes_normal.png"))
+            .unwrap()
+            .to_rgba8();
+        assert_eq!((normal.width(), normal.height()), (8, 8));
+        assert!(dir.join("out/cubes_mask.png").exists());
+    }
+
+    #[test]
+    fn missing_mesh_is_render_error() {
+        let dir = temp_dir("missing_mesh");
+        let cfg = dir.join("cfg.json");
+        write_cfg(
+            &cfg,
+            &dir.join("missing.obj").to_string_lossy(),
+            r#"["Normal"]"#,
+        );
+        let jobs = load_jobs(&[cfg], &[]).unwrap();
+
+        match render_job(&jobs[0], &output(&dir, "{pass}.png")) {
+            Err(BatchError::Render(..)) => {}
+            _ => panic!("expected a render error"),
+        }
+        assert!(!dir.join("normal.png").exists());
+    }
+}
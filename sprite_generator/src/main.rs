@@ -1,119 +1,717 @@
-use std::thread;
+mod batch;
 mod renderer;
-mod time;
-use renderer::{CameraSettings, Perspective, ShadingModes, SpriteRenderer, Vec3};
+#[cfg(test)]
+mod test_util;
+use batch::{BatchError, Job, Output};
+use raytracer_core::time;
+use renderer::{
+    AmbientOcclusionSettings, CameraSettings, LightingSettings, NormalMapSettings, OutlineSettings,
+    PixelArtSettings, SamplingSettings, ShadingModes, ToonSettings,
+};
+use std::path::{Path, PathBuf};
+use std::process;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(
+    name = "sprite_generator",
+    about = "Renders sprite passes of OBJ meshes"
+)]
+struct Args {
+    #[structopt(
+        parse(from_os_str),
+        help = "Config files to render, without any cfg.json is watched as with --watch"
+    )]
+    configs: Vec<PathBuf>,
+    #[structopt(
+        short,
+        long,
+        parse(from_os_str),
+        help = "Manifest listing meshes to render with a shared config"
+    )]
+    manifest: Vec<PathBuf>,
+    #[structopt(short, long, help = "Re-render whenever the config or mesh changes")]
+    watch: bool,
+    #[structopt(
+        short,
+        long,
+        parse(from_os_str),
+        default_value = ".",
+        help = "Directory the rendered passes are written to"
+    )]
+    output_dir: PathBuf,
+    #[structopt(
+        short,
+        long,
+        default_value = "{pass}.png",
+        help = "Output file name, {config}, {mesh} and {pass} are replaced by their file stems"
+    )]
+    name: String,
+    #[structopt(long, help = "Print an example config with every default and exit")]
+    print_default_config: bool,
+    #[structopt(long, help = "Print the JSON Schema of the config file and exit")]
+    print_schema: bool,
+}
 
 fn main() {
-    let mut cfg_watcher = cli::CfgWatcher::new("cfg.json");
-    loop {
-        match cfg_watcher.poll() {
-            Some(cfg) => {
-                execution_pass(&cfg);
-            }
-            None => {
-                thread::sleep(std::time::Duration::from_micros(100));
+    let mut args = Args::from_args();
+    if args.print_default_config {
+        print!("{}", cli::DEFAULT_CONFIG);
+        return;
+    }
+    if args.print_schema {
+        println!("{}", cli::schema());
+        return;
+    }
+
+    if args.configs.is_empty() && args.manifest.is_empty() {
+        args.configs.push(PathBuf::from("cfg.json"));
+        args.watch = true;
+    }
+
+    let output = Output {
+        dir: args.output_dir,
+        name: args.name,
+    };
+
+    if args.watch {
+        if args.configs.len() != 1 || !args.manifest.is_empty() {
+            eprintln!("Error: --watch takes a single config file");
+            process::exit(2);
+        }
+        watch(&args.configs[0], &output);
+    } else {
+        match run_batch(&args.configs, &args.manifest, &output) {
+            Ok(()) => {}
+            Err(BatchFailure::NotStarted) => process::exit(1),
+            Err(BatchFailure::Jobs(failures)) => {
+                eprintln!("{} job(s) failed", failures);
+                process::exit(1);
             }
         }
     }
 }
 
-fn execution_pass(cfg: &cli::RenderSettings) {
-    let clock = time::Clock::new();
+fn watch(config: &Path, output: &Output) {
+    let mut cfg_watcher = match cli::CfgWatcher::new(config) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            eprintln!("Unable to watch '{}': {}", config.display(), e);
+            process::exit(1);
+        }
+    };
 
-    let camera_settings = cfg.camera_settings;
+    while let Some(settings) = cfg_watcher.next() {
+        let job = [Job::new(config, settings)];
+        let result =
+            batch::check_outputs(&job, output).and_then(|_| execution_pass(&job[0], output));
+        if let Err(e) = result {
+            eprintln!("Error: {}", e);
+        }
+    }
+}
 
-    let image_width = cfg.image_width;
-    let image_height = cfg.image_height;
+// Why a batch failed, once its errors have been reported
+enum BatchFailure {
+    // The jobs couldn't be loaded or would overwrite each other's outputs, nothing was rendered
+    NotStarted,
+    // That many jobs failed to render
+    Jobs(usize),
+}
 
-    let mesh_file = cfg.mesh_file.clone();
+// Renders every job, continuing past failures
+fn run_batch(
+    configs: &[PathBuf],
+    manifests: &[PathBuf],
+    output: &Output,
+) -> Result<(), BatchFailure> {
+    let jobs = batch::load_jobs(configs, manifests)
+        .and_then(|jobs| batch::check_outputs(&jobs, output).map(|_| jobs))
+        .map_err(|e| {
+            eprintln!("Error: {}", e);
+            BatchFailure::NotStarted
+        })?;
 
-    let mut renderer = SpriteRenderer {
-        image_width,
-        image_height,
-        shading_mode: ShadingModes::Normal,
-        camera_settings,
-        mesh_file,
-        blacken_normal_map: cfg.blacken_normal_map,
-    };
+    let failures = jobs
+        .iter()
+        .filter(|job| match execution_pass(job, output) {
+            Ok(()) => false,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                true
+            }
+        })
+        .count();
 
-    let img = renderer.render();
-    img.save("normal.png").unwrap();
+    match failures {
+        0 => Ok(()),
+        n => Err(BatchFailure::Jobs(n)),
+    }
+}
 
-    renderer.shading_mode = ShadingModes::Diffuse;
+fn execution_pass(job: &Job, output: &Output) -> Result<(), BatchError> {
+    let clock = time::Clock::new();
 
-    let img = renderer.render();
-    img.save("diffuse.png").unwrap();
+    batch::render_job(job, output)?;
 
-    println!("Run time: {:?}.", clock.elapsed());
+    println!(
+        "Rendered '{}' in {:?}.",
+        job.settings.mesh_file,
+        clock.elapsed()
+    );
+    Ok(())
 }
 
 mod cli {
     use super::*;
+    use notify::{DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+    use schemars::{schema_for, JsonSchema};
+    use serde::de::DeserializeOwned;
     use serde::{Deserialize, Serialize};
-    use serde_json::Result;
     use std::fs::File;
     use std::io::prelude::*;
-    use std::path::Path;
-    use std::time::SystemTime;
+    use std::path::{Path, PathBuf};
+    use std::sync::mpsc::{channel, Receiver};
+    use std::time::Duration;
+
+    // Editors often write a file in several steps, so wait for writes to settle before reloading
+    const DEBOUNCE: Duration = Duration::from_millis(250);
 
+    // Re-renders whenever the config or the mesh it references changes on disk.
     pub struct CfgWatcher {
-        last_updated: Option<SystemTime>,
-        file: &'static str,
+        file: PathBuf,
+        mesh_file: Option<PathBuf>,
+        watcher: RecommendedWatcher,
+        events: Receiver<DebouncedEvent>,
+        watched_dirs: Vec<PathBuf>,
+        reload: bool,
     }
 
     impl CfgWatcher {
-        pub fn new(file: &'static str) -> Self {
-            Self {
-                file: file,
-                last_updated: None,
-            }
+        pub fn new(file: &Path) -> notify::Result<Self> {
+            let (tx, events) = channel();
+            let watcher = notify::watcher(tx, DEBOUNCE)?;
+
+            let mut cfg_watcher = Self {
+                file: PathBuf::new(),
+                mesh_file: None,
+                watcher,
+                events,
+                watched_dirs: vec![],
+                reload: true,
+            };
+            cfg_watcher.file = cfg_watcher.watch(file)?;
+
+            Ok(cfg_watcher)
         }
 
-        pub fn poll(&mut self) -> Option<RenderSettings> {
-            let (settings, modified_at) = {
-                match from_cfg_file(self.file) {
-                    Ok(val) => val,
-                    Err(e) => {
-                        println!("{:?}", e);
-                        return None;
+        // Blocks until there are new settings to render. Returns None if the watcher stops.
+        pub fn next(&mut self) -> Option<RenderSettings> {
+            loop {
+                if self.reload {
+                    self.reload = false;
+
+                    match load_cfg(&self.file) {
+                        Ok(settings) => {
+                            self.watch_mesh(&settings.mesh_file);
+                            return Some(settings);
+                        }
+                        Err(e) => eprintln!("{}", e),
                     }
                 }
+
+                match self.events.recv() {
+                    Ok(event) => self.reload = self.is_relevant(event),
+                    Err(_) => return None,
+                }
+            }
+        }
+
+        fn is_relevant(&self, event: DebouncedEvent) -> bool {
+            let path = match event {
+                DebouncedEvent::Create(path)
+                | DebouncedEvent::Write(path)
+                | DebouncedEvent::Rename(_, path) => path,
+                DebouncedEvent::Rescan => return true,
+                DebouncedEvent::Error(e, _) => {
+                    eprintln!("Watch error: {}", e);
+                    return false;
+                }
+                _ => return false,
             };
-            match self.last_updated {
-                Some(last_execution) => {
-                    if last_execution < modified_at {
-                        self.last_updated = Some(modified_at);
-                        return Some(settings);
-                    }
+
+            path == self.file || Some(&path) == self.mesh_file.as_ref()
+        }
+
+        fn watch_mesh(&mut self, mesh_file: &str) {
+            match self.watch(Path::new(mesh_file)) {
+                Ok(path) => self.mesh_file = Some(path),
+                Err(e) => {
+                    self.mesh_file = None;
+                    eprintln!("Unable to watch '{}': {}", mesh_file, e);
                 }
+            }
+        }
+
+        // Watches the directory instead of the file, as many editors save by replacing the file.
+        // Returns the absolute path events for the file will be reported with.
+        fn watch(&mut self, file: &Path) -> notify::Result<PathBuf> {
+            let dir = match file.parent() {
+                Some(dir) if dir != Path::new("") => dir,
+                _ => Path::new("."),
+            };
+            let dir = dir.canonicalize()?;
+
+            let path = match file.file_name() {
+                Some(name) => dir.join(name),
                 None => {
-                    self.last_updated = Some(modified_at);
-                    return Some(settings);
+                    return Err(notify::Error::Generic(format!(
+                        "'{}' is not a file",
+                        file.display()
+                    )))
                 }
             };
 
-            None
+            if !self.watched_dirs.contains(&dir) {
+                self.watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+                self.watched_dirs.push(dir);
+            }
+
+            Ok(path)
         }
     }
 
-    #[derive(Serialize, Deserialize)]
+    #[derive(Debug)]
+    pub enum CfgError {
+        Io(PathBuf, std::io::Error),
+        Parse(PathBuf, serde_json::Error),
+        Invalid(PathBuf, Vec<String>),
+    }
+
+    impl std::fmt::Display for CfgError {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            match self {
+                CfgError::Io(path, e) => write!(f, "Unable to read '{}': {}", path.display(), e),
+                // serde_json already reports the line and column of the error
+                CfgError::Parse(path, e) => write!(f, "Invalid config '{}': {}", path.display(), e),
+                CfgError::Invalid(path, errors) => {
+                    write!(f, "Invalid config '{}':", path.display())?;
+                    for e in errors {
+                        write!(f, "\n  {}", e)?;
+                    }
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+    #[serde(deny_unknown_fields)]
     pub struct RenderSettings {
-        pub just_updated: Option<bool>,
-        pub camera_settings: CameraSettings,
+        // Lets editors find the schema written by --print-schema
+        #[serde(rename = "$schema", skip_serializing_if = "Option::is_none")]
+        pub schema: Option<String>,
+        pub mesh_file: String,
+        #[serde(default = "default_image_size")]
         pub image_width: u32,
+        #[serde(default = "default_image_size")]
         pub image_height: u32,
-        pub mesh_file: String,
+        #[serde(default)]
+        pub camera_settings: CameraSettings,
+        #[serde(default)]
         pub blacken_normal_map: bool,
+        #[serde(default)]
+        pub normal_map: NormalMapSettings,
+        #[serde(default = "default_passes")]
+        pub passes: Vec<ShadingModes>,
+        #[serde(default)]
+        pub ambient_occlusion: AmbientOcclusionSettings,
+        #[serde(default)]
+        pub lighting: LightingSettings,
+        #[serde(default)]
+        pub sampling: SamplingSettings,
+        #[serde(default)]
+        pub pixel_art: PixelArtSettings,
+        #[serde(default)]
+        pub outline: Option<OutlineSettings>,
+        #[serde(default)]
+        pub toon: Option<ToonSettings>,
+    }
+
+    fn default_image_size() -> u32 {
+        64
+    }
+
+    fn default_passes() -> Vec<ShadingModes> {
+        vec![ShadingModes::Normal, ShadingModes::Diffuse]
+    }
+
+    impl RenderSettings {
+        // Settings serde accepts but that can't produce a sensible render, one message per problem
+        pub fn validate(&self) -> Vec<String> {
+            let mut errors = vec![];
+
+            if self.mesh_file.is_empty() {
+                errors.push("mesh_file must not be empty".to_string());
+            }
+            if self.image_width == 0 || self.image_height == 0 {
+                errors.push(format!(
+                    "image_width and image_height must be at least 1, got {}x{}",
+                    self.image_width, self.image_height
+                ));
+            }
+            if self.passes.is_empty() {
+                errors.push("passes must list at least one pass".to_string());
+            }
+
+            for e in self.camera_settings.validate() {
+                errors.push(format!("camera_settings: {}", e));
+            }
+
+            let ao = &self.ambient_occlusion;
+            if ao.samples == 0 {
+                errors.push("ambient_occlusion: samples must be at least 1".to_string());
+            }
+            if ao.distance <= 0. {
+                errors.push(format!(
+                    "ambient_occlusion: distance must be positive, got {}",
+                    ao.distance
+                ));
+            }
+
+            for (i, light) in self.lighting.lights.iter().enumerate() {
+                if light.direction.len() == 0. {
+                    errors.push(format!(
+                        "lighting: lights[{}] direction must not be zero",
+                        i
+                    ));
+                }
+                if light.intensity < 0. {
+                    errors.push(format!(
+                        "lighting: lights[{}] intensity must not be negative, got {}",
+                        i, light.intensity
+                    ));
+                }
+            }
+
+            if self.sampling.default.grid_size == 0 {
+                errors.push("sampling: default grid_size must be at least 1".to_string());
+            }
+            for (pass, sampling) in &self.sampling.passes {
+                if sampling.grid_size == 0 {
+                    errors.push(format!("sampling: {:?} grid_size must be at least 1", pass));
+                }
+            }
+
+            if self.pixel_art.scale == 0 {
+                errors.push("pixel_art: scale must be at least 1".to_string());
+            }
+            if let Some(outline) = &self.outline {
+                if outline.depth_threshold < 0. {
+                    errors.push(format!(
+                        "outline: depth_threshold must not be negative, got {}",
+                        outline.depth_threshold
+                    ));
+                }
+            }
+            if let Some(toon) = &self.toon {
+                if toon.bands == 0 {
+                    errors.push("toon: bands must be at least 1".to_string());
+                }
+            }
+
+            errors
+        }
     }
 
-    fn from_cfg_file(file: &'static str) -> Result<(RenderSettings, SystemTime)> {
-        let path = Path::new(file);
-        let mut file = File::open(path).unwrap();
-        let modified = file.metadata().unwrap().modified().unwrap();
+    // Example config printed by --print-default-config, every optional setting at its default
+    pub const DEFAULT_CONFIG: &str = include_str!("../res/default_cfg.jsonc");
+
+    pub fn schema() -> String {
+        serde_json::to_string_pretty(&schema_for!(RenderSettings)).unwrap()
+    }
+
+    pub fn load_cfg(path: &Path) -> Result<RenderSettings, CfgError> {
+        let settings: RenderSettings = from_cfg_file(path)?;
+
+        let errors = settings.validate();
+        if !errors.is_empty() {
+            return Err(CfgError::Invalid(path.into(), errors));
+        }
+
+        Ok(settings)
+    }
+
+    pub fn from_cfg_file<T: DeserializeOwned>(path: &Path) -> Result<T, CfgError> {
         let mut contents = String::new();
-        file.read_to_string(&mut contents).unwrap();
-        let settings: RenderSettings = serde_json::from_str(&contents)?;
+        File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut contents))
+            .map_err(|e| CfgError::Io(path.into(), e))?;
+
+        serde_json::from_str(&strip_comments(&contents))
+            .map_err(|e| CfgError::Parse(path.into(), e))
+    }
+
+    // Blanks out `//` comments, keeping line breaks so parse errors still point at the right line
+    fn strip_comments(json: &str) -> String {
+        let mut out = String::with_capacity(json.len());
+        let mut in_string = false;
+        let mut in_comment = false;
+        let mut escaped = false;
+        let mut chars = json.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if in_comment {
+                if c == '\n' {
+                    in_comment = false;
+                    out.push(c);
+                }
+                continue;
+            }
+
+            if in_string {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == '"' {
+                    in_string = false;
+                }
+            } else if c == '"' {
+                in_string = true;
+            } else if c == '/' && chars.peek() == Some(&'/') {
+                in_comment = true;
+                continue;
+            }
+
+            out.push(c);
+        }
+
+        out
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::test_util::temp_dir;
+        use std::fs;
+        use std::time::Instant;
+
+        fn write_cfg(path: &Path, mesh_file: &Path, image_width: u32) {
+            let cfg = format!(
+                r#"{{
+    "camera_settings": {{
+        "perspective": {{ "Perspective": {{ "scale": 1 }} }},
+        "eye": {{ "x": 0.0, "y": 0.0, "z": 4.0 }},
+        "target": {{ "x": 0.0, "y": 0.0, "z": 0.0 }},
+        "up_dir": {{ "x": 0.0, "y": 1.0, "z": 0.0 }},
+        "v_fov": 30.0,
+        "focal_len": 1
+    }},
+    "image_width": {},
+    "image_height": 8,
+    "mesh_file": {:?},
+    "blacken_normal_map": false
+}}"#,
+                image_width,
+                mesh_file.to_str().unwrap()
+            );
+            fs::write(path, cfg).unwrap();
+        }
+
+        // Runs `next` on another thread so a missed event fails the test instead of hanging it
+        fn next_within(mut watcher: CfgWatcher, timeout: Duration) -> (CfgWatcher, Option<u32>) {
+            let (tx, rx) = channel();
+            std::thread::spawn(move || {
+                let width = watcher.next().map(|cfg| cfg.image_width);
+                let _ = tx.send((watcher, width));
+            });
+            let start = Instant::now();
+            loop {
+                if let Ok(result) = rx.try_recv() {
+                    return result;
+                }
+                assert!(start.elapsed() < timeout, "watcher did not reload");
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        }
+
+        #[test]
+        fn missing_cfg_is_io_error() {
+            let dir = temp_dir("missing_cfg");
+            match from_cfg_file::<RenderSettings>(&dir.join("cfg.json")) {
+                Err(CfgError::Io(..)) => {}
+                _ => panic!("expected an io error"),
+            }
+        }
+
+        #[test]
+        fn malformed_cfg_reports_line() {
+            let dir = temp_dir("malformed_cfg");
+            let path = dir.join("cfg.json");
+            fs::write(
+                &path,
+                "{\n    \"image_width\": 8,\n    \"image_height\": ,\n}",
+            )
+            .unwrap();
+
+            match from_cfg_file::<RenderSettings>(&path) {
+                Err(e @ CfgError::Parse(..)) => {
+                    if let CfgError::Parse(_, json) = &e {
+                        assert_eq!(json.line(), 3);
+                    }
+                    assert!(e.to_string().contains("line 3"));
+                }
+                _ => panic!("expected a parse error"),
+            }
+        }
+
+        fn invalid_errors(json: &str) -> Vec<String> {
+            let dir = temp_dir(&format!("invalid_{}", json.len()));
+            let path = dir.join("cfg.json");
+            fs::write(&path, json).unwrap();
+
+            match load_cfg(&path) {
+                Err(CfgError::Invalid(_, errors)) => errors,
+                Err(e) => panic!("expected validation errors, got {}", e),
+                Ok(_) => panic!("expected validation errors"),
+            }
+        }
+
+        #[test]
+        fn minimal_cfg_uses_defaults() {
+            let settings: RenderSettings =
+                serde_json::from_str(r#"{ "mesh_file": "res/cubes.obj" }"#).unwrap();
+
+            assert_eq!(settings.image_width, 64);
+            assert_eq!(settings.passes, default_passes());
+            assert_eq!(settings.camera_settings, CameraSettings::default());
+            assert_eq!(settings.lighting, LightingSettings::default());
+            assert!(settings.validate().is_empty());
+        }
+
+        #[test]
+        fn partial_sections_use_defaults() {
+            let settings: RenderSettings = serde_json::from_str(
+                r#"{ "mesh_file": "a.obj", "ambient_occlusion": { "samples": 8 } }"#,
+            )
+            .unwrap();
+
+            assert_eq!(settings.ambient_occlusion.samples, 8);
+            assert_eq!(
+                settings.ambient_occlusion.distance,
+                AmbientOcclusionSettings::default().distance
+            );
+        }
+
+        #[test]
+        fn default_config_matches_defaults() {
+            let settings: RenderSettings =
+                serde_json::from_str(&strip_comments(DEFAULT_CONFIG)).unwrap();
+            let mut minimal: RenderSettings =
+                serde_json::from_str(r#"{ "mesh_file": "res/cubes.obj" }"#).unwrap();
+            minimal.schema = Some("cfg.schema.json".to_string());
+
+            assert_eq!(settings, minimal);
+        }
+
+        // The committed file is written by the default f32 build, f64 changes the number formats.
+        // Checking R too catches f64 turned on through raytracer_core rather than this crate.
+        #[test]
+        #[cfg_attr(feature = "f64", ignore)]
+        fn schema_file_is_up_to_date() {
+            if std::mem::size_of::<crate::renderer::R>() != std::mem::size_of::<f32>() {
+                eprintln!("skipped, R is not f32");
+                return;
+            }
+            let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("cfg.schema.json");
+            let committed = fs::read_to_string(path).unwrap();
+
+            assert_eq!(committed.trim_end(), schema(), "run with --print-schema");
+        }
+
+        #[test]
+        fn unknown_fields_are_rejected() {
+            let result: serde_json::Result<RenderSettings> =
+                serde_json::from_str(r#"{ "mesh_file": "a.obj", "image_widht": 8 }"#);
 
-        Ok((settings, modified))
+            assert!(result.unwrap_err().to_string().contains("image_widht"));
+        }
+
+        #[test]
+        fn comments_are_stripped_outside_strings() {
+            let json = "{\n  // comment \"quoted\"\n  \"a\": \"http://x\" // trailing\n}";
+            let value: serde_json::Value = serde_json::from_str(&strip_comments(json)).unwrap();
+
+            assert_eq!(value["a"], "http://x");
+            assert_eq!(strip_comments(json).lines().count(), json.lines().count());
+        }
+
+        #[test]
+        fn rejects_zero_size() {
+            let errors = invalid_errors(r#"{ "mesh_file": "a.obj", "image_width": 0 }"#);
+
+            assert_eq!(errors.len(), 1);
+            assert!(errors[0].contains("image_width"));
+        }
+
+        #[test]
+        fn rejects_up_dir_parallel_to_view() {
+            let errors = invalid_errors(
+                r#"{
+                    "mesh_file": "a.obj",
+                    "camera_settings": {
+                        "eye": { "x": 0.0, "y": 5.0, "z": 0.0 },
+                        "up_dir": { "x": 0.0, "y": 2.0, "z": 0.0 }
+                    }
+                }"#,
+            );
+
+            assert_eq!(
+                errors,
+                vec!["camera_settings: up_dir must not be parallel to the view direction"]
+            );
+        }
+
+        #[test]
+        fn reports_every_problem() {
+            let errors = invalid_errors(
+                r#"{
+                    "mesh_file": "a.obj",
+                    "passes": [],
+                    "camera_settings": { "v_fov": -5.0 },
+                    "pixel_art": { "scale": 0 }
+                }"#,
+            );
+
+            assert_eq!(errors.len(), 3);
+            assert!(errors.iter().any(|e| e.contains("v_fov")));
+            assert!(errors.iter().any(|e| e.contains("passes")));
+            assert!(errors.iter().any(|e| e.contains("pixel_art")));
+        }
+
+        #[test]
+        fn reloads_on_cfg_and_mesh_changes() {
+            let dir = temp_dir("watcher");
+            let cfg = dir.join("cfg.json");
+            let mesh = dir.join("mesh.obj");
+            fs::write(&mesh, "").unwrap();
+            write_cfg(&cfg, &mesh, 8);
+
+            let watcher = CfgWatcher::new(&cfg).unwrap();
+            let timeout = Duration::from_secs(10);
+
+            // The first call loads the config without waiting for a change
+            let (watcher, width) = next_within(watcher, timeout);
+            assert_eq!(width, Some(8));
+
+            write_cfg(&cfg, &mesh, 16);
+            let (watcher, width) = next_within(watcher, timeout);
+            assert_eq!(width, Some(16));
+
+            fs::write(&mesh, "v 0 0 0").unwrap();
+            let (_, width) = next_within(watcher, timeout);
+            assert_eq!(width, Some(16));
+        }
     }
 }
@@ -0,0 +1,211 @@
+use super::color::Color;
+use super::math::R;
+use image::{ImageBuffer, Rgba, RgbaImage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutlineSettings {
+    pub color: Color,
+    // In output pixels
+    pub thickness: u32,
+    // How far behind its neighbour a pixel has to be, as a fraction of its depth, to get an inner line
+    pub depth_threshold: R,
+}
+
+impl Default for OutlineSettings {
+    fn default() -> Self {
+        Self {
+            color: Color::new(0., 0., 0., 1.),
+            thickness: 1,
+            depth_threshold: 0.1,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct ToonSettings {
+    pub bands: u32,
+}
+
+impl Default for ToonSettings {
+    fn default() -> Self {
+        Self { bands: 3 }
+    }
+}
+
+// Draws lines outside the silhouette, and on the far side of depth discontinuities inside it.
+// `depth` holds the distance to the mesh for each pixel, in the same order as the image pixels.
+pub fn outline(
+    img: &RgbaImage,
+    depth: &[Option<R>],
+    settings: &OutlineSettings,
+    thickness: u32,
+) -> RgbaImage {
+    let width = img.width() as i64;
+    let height = img.height() as i64;
+    let radius = thickness as i64;
+    let line: [u8; 4] = settings.color.into();
+
+    let covered = |x: i64, y: i64| img.get_pixel(x as u32, y as u32)[3] > 0;
+    let depth_at = |x: i64, y: i64| depth[(y * width + x) as usize];
+
+    ImageBuffer::from_fn(img.width(), img.height(), |x, y| {
+        let x = x as i64;
+        let y = y as i64;
+        let d = depth_at(x, y);
+
+        for ny in (y - radius).max(0)..(y + radius + 1).min(height) {
+            for nx in (x - radius).max(0)..(x + radius + 1).min(width) {
+                let (dx, dy) = (nx - x, ny - y);
+                if dx * dx + dy * dy > radius * radius {
+                    continue;
+                }
+
+                let edge = {
+                    if !covered(x, y) {
+                        covered(nx, ny)
+                    } else {
+                        match (d, depth_at(nx, ny)) {
+                            (Some(d), Some(nd)) => d - nd > settings.depth_threshold * d,
+                            _ => false,
+                        }
+                    }
+                };
+
+                if edge {
+                    return Rgba(line);
+                }
+            }
+        }
+
+        *img.get_pixel(x as u32, y as u32)
+    })
+}
+
+// Snaps the brightness of each pixel up to one of `bands` levels, keeping its hue
+pub fn toon(img: &RgbaImage, settings: &ToonSettings) -> RgbaImage {
+    let bands = settings.bands.max(1) as R;
+
+    ImageBuffer::from_fn(img.width(), img.height(), |x, y| {
+        let p = img.get_pixel(x, y);
+        let color = Color::from_u8(p[0], p[1], p[2], p[3]);
+        let luminance = 0.2126 * color.r + 0.7152 * color.g + 0.0722 * color.b;
+        if p[3] == 0 || luminance <= 0. {
+            return *p;
+        }
+
+        let banded = (luminance * bands).ceil() / bands;
+        let mut color = color * (banded / luminance);
+        color.a = p[3] as R / 255.;
+
+        let mut out: [u8; 4] = color.into();
+        out[3] = p[3];
+        Rgba(out)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A 2x2 opaque square in the middle of a 6x6 image
+    fn square() -> (RgbaImage, Vec<Option<R>>) {
+        let inside = |x: u32, y: u32| (2..4).contains(&x) && (2..4).contains(&y);
+        let img = ImageBuffer::from_fn(6, 6, |x, y| {
+            if inside(x, y) {
+                Rgba([255, 255, 255, 255])
+            } else {
+                Rgba([0, 0, 0, 0])
+            }
+        });
+
+        let mut depth = vec![];
+        for y in 0..6 {
+            for x in 0..6 {
+                depth.push(if inside(x, y) { Some(1.) } else { None });
+            }
+        }
+
+        (img, depth)
+    }
+
+    fn red_outline() -> OutlineSettings {
+        OutlineSettings {
+            color: Color::new(1., 0., 0., 1.),
+            ..OutlineSettings::default()
+        }
+    }
+
+    #[test]
+    fn outline_surrounds_silhouette() {
+        let (img, depth) = square();
+        let actual = outline(&img, &depth, &red_outline(), 1);
+
+        let red = Rgba([255, 0, 0, 255]);
+        assert_eq!(&red, actual.get_pixel(1, 2));
+        assert_eq!(&red, actual.get_pixel(4, 3));
+        assert_eq!(&red, actual.get_pixel(2, 1));
+        // Diagonals are outside a radius of 1
+        assert_eq!(0, actual.get_pixel(1, 1)[3]);
+        assert_eq!(0, actual.get_pixel(0, 0)[3]);
+        // The silhouette itself is untouched
+        assert_eq!(&Rgba([255, 255, 255, 255]), actual.get_pixel(2, 2));
+    }
+
+    #[test]
+    fn outline_thickness_widens_line() {
+        let (img, depth) = square();
+        let actual = outline(&img, &depth, &red_outline(), 2);
+
+        assert_eq!(&Rgba([255, 0, 0, 255]), actual.get_pixel(0, 2));
+        assert_eq!(&Rgba([255, 0, 0, 255]), actual.get_pixel(1, 1));
+    }
+
+    #[test]
+    fn outline_marks_depth_discontinuities() {
+        let img = ImageBuffer::from_pixel(4, 1, Rgba([255, 255, 255, 255]));
+        let depth = vec![Some(1.), Some(1.), Some(5.), Some(5.)];
+        let actual = outline(&img, &depth, &red_outline(), 1);
+
+        // Only the far side of the step gets a line
+        assert_eq!(&Rgba([255, 255, 255, 255]), actual.get_pixel(1, 0));
+        assert_eq!(&Rgba([255, 0, 0, 255]), actual.get_pixel(2, 0));
+        assert_eq!(&Rgba([255, 255, 255, 255]), actual.get_pixel(3, 0));
+    }
+
+    #[test]
+    fn outline_ignores_gradual_depth() {
+        let img = ImageBuffer::from_pixel(4, 1, Rgba([255, 255, 255, 255]));
+        let depth = vec![Some(1.), Some(1.05), Some(1.1), Some(1.15)];
+        let actual = outline(&img, &depth, &red_outline(), 1);
+
+        assert_eq!(img, actual);
+    }
+
+    #[test]
+    fn toon_limits_brightness_levels() {
+        let img = ImageBuffer::from_fn(256, 1, |x, _| Rgba([x as u8, x as u8, x as u8, 255]));
+        let actual = toon(&img, &ToonSettings { bands: 3 });
+
+        let mut levels: Vec<u8> = actual.pixels().map(|p| p[0]).collect();
+        levels.dedup();
+        // Black stays black, everything else lands on one of the bands
+        assert_eq!(4, levels.len());
+        assert_eq!(255, *levels.last().unwrap());
+    }
+
+    #[test]
+    fn toon_keeps_hue_and_alpha() {
+        let img = ImageBuffer::from_pixel(1, 1, Rgba([0, 100, 100, 128]));
+        let actual = toon(&img, &ToonSettings { bands: 2 });
+
+        let p = actual.get_pixel(0, 0);
+        assert_eq!(0, p[0]);
+        assert_eq!(p[1], p[2]);
+        assert!(p[1] > 100);
+        assert_eq!(128, p[3]);
+    }
+}
@@ -1,28 +1,72 @@
 use image::{ImageBuffer, Rgba, RgbaImage};
 use rayon::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-pub mod camera;
-pub mod color;
-pub mod hittable;
-pub mod math;
-pub mod ray;
+pub mod effects;
+pub mod normals;
+pub mod pixel_art;
+pub mod sampling;
 
+pub use raytracer_core::{camera, color, hittable, lighting, math, ray};
+
+use camera::Camera;
 pub use camera::CameraSettings;
 use color::Color;
+pub use effects::{OutlineSettings, ToonSettings};
 use hittable::{
-    materials::Material,
-    objects::{Mesh, Sphere, World},
-    Hittable,
+    objects::{Mesh, MeshError, World},
+    HitRecord, Hittable,
 };
+pub use lighting::LightingSettings;
 pub use math::{Vec3, INFINITY, R};
+pub use normals::NormalMapSettings;
+pub use pixel_art::PixelArtSettings;
+use pixel_art::{Palette, PaletteError};
 use ray::Ray;
+pub use sampling::SamplingSettings;
 
-pub use camera::Perspective;
-
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize, JsonSchema)]
 pub enum ShadingModes {
     Diffuse,
     Normal,
+    Depth,
+    AmbientOcclusion,
+    Mask,
+    MaterialId,
+    Lit,
+}
+
+impl ShadingModes {
+    // Used as the `{pass}` part of output file names
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShadingModes::Diffuse => "diffuse",
+            ShadingModes::Normal => "normal",
+            ShadingModes::Depth => "depth",
+            ShadingModes::AmbientOcclusion => "ao",
+            ShadingModes::Mask => "mask",
+            ShadingModes::MaterialId => "id",
+            ShadingModes::Lit => "lit",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct AmbientOcclusionSettings {
+    pub samples: u32,
+    // Occluders further away than this don't darken the surface
+    pub distance: R,
+}
+
+impl Default for AmbientOcclusionSettings {
+    fn default() -> Self {
+        Self {
+            samples: 64,
+            distance: 1.,
+        }
+    }
 }
 
 pub struct SpriteRenderer {
@@ -32,29 +76,66 @@ pub struct SpriteRenderer {
     pub camera_settings: CameraSettings,
     pub mesh_file: String,
     pub blacken_normal_map: bool,
+    pub normal_map: NormalMapSettings,
+    pub ambient_occlusion: AmbientOcclusionSettings,
+    pub lighting: LightingSettings,
+    pub sampling: SamplingSettings,
+    pub pixel_art: PixelArtSettings,
+    pub outline: Option<OutlineSettings>,
+    pub toon: Option<ToonSettings>,
+}
+
+#[derive(Debug)]
+pub enum RenderError {
+    Mesh(MeshError),
+    Palette(PaletteError),
+}
+
+impl std::fmt::Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RenderError::Mesh(e) => e.fmt(f),
+            RenderError::Palette(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+impl From<MeshError> for RenderError {
+    fn from(e: MeshError) -> Self {
+        RenderError::Mesh(e)
+    }
+}
+
+impl From<PaletteError> for RenderError {
+    fn from(e: PaletteError) -> Self {
+        RenderError::Palette(e)
+    }
 }
 
 impl SpriteRenderer {
-    pub fn render(&self) -> RgbaImage {
-        render(
-            self.image_width,
-            self.image_height,
-            self.shading_mode,
-            self.blacken_normal_map,
-            self.camera_settings,
-            self.mesh_file.clone(),
-        )
+    pub fn render(&self) -> Result<RgbaImage, RenderError> {
+        render(self)
     }
 }
 
-fn render(
-    image_width: u32,
-    image_height: u32,
-    shading_mode: ShadingModes,
-    blacken_normal_map: bool,
-    camera_settings: CameraSettings,
-    mesh_file: String,
-) -> RgbaImage {
+fn render(renderer: &SpriteRenderer) -> Result<RgbaImage, RenderError> {
+    // Rendered at a multiple of the output size, then downsampled
+    let scale = renderer.pixel_art.scale.max(1);
+    let image_width = renderer.image_width * scale;
+    let image_height = renderer.image_height * scale;
+    let shading_mode = renderer.shading_mode;
+    let mut camera_settings = renderer.camera_settings;
+
+    // Only color passes are quantized, as palette colors mean nothing for normals or depth
+    let palette = match (&renderer.pixel_art.palette_file, shading_mode) {
+        (Some(file), ShadingModes::Diffuse) | (Some(file), ShadingModes::Lit) => {
+            Some(Palette::from_file(file)?)
+        }
+        _ => None,
+    };
+
     // Screen
     let aspect_ratio = (image_width as R) / (image_height as R);
 
@@ -63,43 +144,24 @@ fn render(
 
     // World
     let world = {
-        // Scale objects so that their width is less than 1.0
         let mut world = World::new();
-        let size_scale = 1.;
-        /*
-                let pos = size_scale * Vec3::new(0.0, 0.0, -1.);
-                world.add_item(Sphere::new(
-                    pos,
-                    0.3 * size_scale,
-                    Material::Lambertian {
-                        albedo: Color::new(1., 0., 0.0, 1.),
-                    },
-                ));
-
-                let pos = size_scale * Vec3::new(1.0, 0.0, -1.);
-                world.add_item(Sphere::new(
-                    pos,
-                    0.3 * size_scale,
-                    Material::Lambertian {
-                        albedo: Color::new(1., 1., 0.0, 1.),
-                    },
-                ));
-
-                let pos = size_scale * Vec3::new(1.0, 1.0, -1.);
-                world.add_item(Sphere::new(
-                    pos,
-                    0.6 * size_scale,
-                    Material::Lambertian {
-                        albedo: Color::new(1., 0., 1.0, 1.),
-                    },
-                ));
-        */
-        let mesh = Mesh::from_obj(mesh_file);
+        let mesh = Mesh::from_obj(renderer.mesh_file.clone())?;
+        if let Some(auto_frame) = camera_settings.auto_frame() {
+            camera_settings = camera_settings.framed(
+                mesh.center(),
+                mesh.bounding_radius(),
+                auto_frame.padding,
+                aspect_ratio,
+            );
+        }
         world.add_item(mesh);
 
         world
     };
 
+    // With a projection preset only the target of the auto framing is kept, the tile size sets the scale
+    let camera_settings = camera_settings.projected(renderer.image_width, renderer.image_height);
+
     // Camera
     let camera = camera_settings.into_camera(aspect_ratio);
 
@@ -120,26 +182,46 @@ fn render(
             let i = *i;
             let j = *j;
 
-            let u = (i as R) / ((image_width - 1) as R);
-            let v = (j as R) / ((image_height - 1) as R);
-
-            let ray = camera.ray(u, v);
-
-            let color = ray_color(&ray, &world, shading_mode);
+            // Seeded per pixel, so a render comes out the same whichever thread draws the pixel
+            math::seed_random((j * image_width + i) as u64);
+            let color = sample_pixel(i, j, image_width, image_height, renderer, &camera, &world);
 
             (i, j, color)
         })
         .collect();
 
+    // Depth is normalized to the range of the visible geometry, with the closest point brightest
+    let (near, far) = colors
+        .iter()
+        .filter(|(_, _, color)| color.a > 0.)
+        .fold((INFINITY, -INFINITY), |(near, far), (_, _, color)| {
+            (near.min(color.r), far.max(color.r))
+        });
+
     // Save img
     for (i, j, color) in colors {
         let color = {
             if shading_mode == ShadingModes::Normal {
-                if color.a == 0. && blacken_normal_map {
-                    Color::new(0., 0., 0., 1.)
+                if renderer.blacken_normal_map {
+                    // Composite partially covered edges over black
+                    Color::new(color.r * color.a, color.g * color.a, color.b * color.a, 1.)
                 } else {
                     color
                 }
+            } else if shading_mode == ShadingModes::Depth {
+                if color.a == 0. {
+                    color
+                } else {
+                    let range = far - near;
+                    let depth = if range > 0. {
+                        1. - (color.r - near) / range
+                    } else {
+                        1.
+                    };
+                    Color::new(depth, depth, depth, color.a)
+                }
+            } else if shading_mode != ShadingModes::Diffuse {
+                color
             } else {
                 let mut color = color;
                 color.r /= 2.;
@@ -152,28 +234,838 @@ fn render(
         img.put_pixel(i, (image_height - 1) - j, Rgba(color.into()));
     }
 
-    img
+    let is_color_pass = shading_mode == ShadingModes::Diffuse || shading_mode == ShadingModes::Lit;
+
+    let img = match (renderer.toon, shading_mode) {
+        (Some(toon), ShadingModes::Lit) => effects::toon(&img, &toon),
+        _ => img,
+    };
+
+    let img = match renderer.outline {
+        Some(outline) if is_color_pass => {
+            let depth = depth_buffer(&camera, &world, image_width, image_height);
+            effects::outline(&img, &depth, &outline, outline.thickness * scale)
+        }
+        _ => img,
+    };
+
+    let img = pixel_art::downsample(&img, scale, renderer.pixel_art.filter);
+    let img = match palette {
+        Some(palette) => pixel_art::quantize(&img, &palette, renderer.pixel_art.dither),
+        None => img,
+    };
+
+    Ok(img)
+}
+
+fn ray_color(ray: &Ray, world: &World, camera: &Camera, renderer: &SpriteRenderer) -> Color {
+    let min_hit = 0.0001;
+    let hr = match world.hit(ray, min_hit, INFINITY) {
+        Some(hr) => hr,
+        None => return Color::new(0., 0., 0., 0.),
+    };
+
+    match renderer.shading_mode {
+        ShadingModes::Diffuse => hr.material.color(),
+        ShadingModes::Normal => {
+            // Now that we have the normal, orient it to the camera.
+            // map x,y,z from -1..1 to 0..1
+            let normal = renderer.normal_map.encode(hr.normal, camera);
+            let normal = (normal + Vec3::one()) / 2.;
+
+            Color::from_vec3(normal, 1.)
+        }
+        ShadingModes::Depth => {
+            // Raw depth, normalized once the whole image is known
+            let depth = camera.depth(hr.point);
+
+            Color::new(depth, depth, depth, 1.)
+        }
+        ShadingModes::AmbientOcclusion => {
+            let ao = occlusion(&hr, world, renderer.ambient_occlusion);
+
+            Color::new(ao, ao, ao, 1.)
+        }
+        ShadingModes::Mask => Color::new(1., 1., 1., 1.),
+        ShadingModes::MaterialId => id_color(hr.material_id),
+        ShadingModes::Lit => renderer.lighting.shade(ray, &hr, world),
+    }
+}
+
+// Combines the samples across the pixel. Alpha is the fraction of samples that hit the mesh.
+fn sample_pixel(
+    i: u32,
+    j: u32,
+    image_width: u32,
+    image_height: u32,
+    renderer: &SpriteRenderer,
+    camera: &Camera,
+    world: &World,
+) -> Color {
+    let sampling = renderer.sampling.for_pass(renderer.shading_mode);
+    let offsets = sampling.offsets();
+
+    let mut hits = vec![];
+    for (u2, v2) in offsets.iter() {
+        let u = ((i as R) + 0.5 + u2) / (image_width as R);
+        let v = ((j as R) + 0.5 + v2) / (image_height as R);
+
+        let ray = camera.ray(u, v);
+
+        let c = ray_color(&ray, world, camera, renderer);
+        if c.a > 0. {
+            hits.push(c);
+        }
+    }
+
+    let mut coverage = (hits.len() as R) / (offsets.len() as R);
+    if sampling.hard_edges {
+        coverage = if coverage >= 0.5 { 1. } else { 0. };
+    }
+
+    if hits.is_empty() || coverage == 0. {
+        return Color::new(0., 0., 0., 0.);
+    }
+
+    let mut color = match renderer.shading_mode {
+        // Blending ids would produce ids that don't exist, so take the most common one
+        ShadingModes::MaterialId => most_common(&hits),
+        ShadingModes::Normal => {
+            let mut sum = Color::new(0., 0., 0., 0.);
+            for c in hits.iter() {
+                sum += *c;
+            }
+            let average = sum / hits.len() as u32;
+
+            // Averaged normals are shorter than unit length, so renormalize them
+            let normal = Vec3::new(average.r, average.g, average.b) * 2. - Vec3::one();
+            let normal = (normal.unit_vector() + Vec3::one()) / 2.;
+
+            Color::from_vec3(normal, 1.)
+        }
+        _ => {
+            let mut sum = Color::new(0., 0., 0., 0.);
+            for c in hits.iter() {
+                sum += *c;
+            }
+            sum / hits.len() as u32
+        }
+    };
+
+    color.a = coverage;
+    color
+}
+
+// Depth of the mesh through the center of each pixel, in image order
+fn depth_buffer(
+    camera: &Camera,
+    world: &World,
+    image_width: u32,
+    image_height: u32,
+) -> Vec<Option<R>> {
+    let min_hit = 0.0001;
+    let mut depth = vec![];
+    for y in 0..image_height {
+        for i in 0..image_width {
+            let j = (image_height - 1) - y;
+            let u = ((i as R) + 0.5) / (image_width as R);
+            let v = ((j as R) + 0.5) / (image_height as R);
+
+            let ray = camera.ray(u, v);
+            depth.push(
+                world
+                    .hit(&ray, min_hit, INFINITY)
+                    .map(|hr| camera.depth(hr.point)),
+            );
+        }
+    }
+
+    depth
+}
+
+fn most_common(colors: &[Color]) -> Color {
+    let mut best = colors[0];
+    let mut best_count = 0;
+    for c in colors.iter() {
+        let count = colors.iter().filter(|other| *other == c).count();
+        if count > best_count {
+            best = *c;
+            best_count = count;
+        }
+    }
+
+    best
 }
 
-fn ray_color(ray: &Ray, world: &World, shading_mode: ShadingModes) -> Color {
+// Fraction of the hemisphere above the hit that is unobstructed. 1 is fully open, 0 is fully occluded.
+fn occlusion(hr: &HitRecord, world: &World, settings: AmbientOcclusionSettings) -> R {
+    if settings.samples == 0 {
+        return 1.;
+    }
+
     let min_hit = 0.0001;
-    match world.hit(ray, min_hit, INFINITY) {
-        Some(hr) => match shading_mode {
-            ShadingModes::Diffuse => {
-                return hr.material.color();
+    let mut occluded = 0;
+    for _ in 0..settings.samples {
+        // Cosine weighted direction around the normal
+        let mut dir = hr.normal + Vec3::random_unit_vector();
+        if dir.near_zero() {
+            dir = hr.normal;
+        }
+
+        // Normalized so the distance is in world units
+        let ray = Ray::new(hr.point, dir.unit_vector()).offset(hr.normal);
+        if world.hit(&ray, min_hit, settings.distance).is_some() {
+            occluded += 1;
+        }
+    }
+
+    1. - (occluded as R) / (settings.samples as R)
+}
+
+// Spreads ids around the hue wheel by the golden ratio so neighbouring ids are easy to tell apart
+fn id_color(id: usize) -> Color {
+    let golden_ratio_conjugate = 0.618034;
+    let hue = ((id as R) * golden_ratio_conjugate).fract() * 6.;
+
+    let x = 1. - (hue % 2. - 1.).abs();
+    let (r, g, b) = match hue as u32 {
+        0 => (1., x, 0.),
+        1 => (x, 1., 0.),
+        2 => (0., 1., x),
+        3 => (0., x, 1.),
+        4 => (x, 0., 1.),
+        _ => (1., 0., x),
+    };
+
+    Color::new(r, g, b, 1.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lighting::{Light, Sky};
+    use super::normals::{Axis, Handedness, NormalSpace};
+    use super::sampling::{SamplePattern, Sampling};
+    use super::*;
+
+    fn cubes_renderer(eye: Vec3, normal_map: NormalMapSettings) -> SpriteRenderer {
+        let mut renderer = cubes_renderer_looking_at(eye, Vec3::new(0., 0., 0.));
+        renderer.normal_map = normal_map;
+        renderer
+    }
+
+    fn cubes_renderer_looking_at(eye: Vec3, target: Vec3) -> SpriteRenderer {
+        let camera_settings = serde_json::from_value(serde_json::json!({
+            "v_fov": 30.0,
+            "eye": eye,
+            "target": target,
+            "up_dir": Vec3::new(0., 1., 0.),
+            "perspective": { "Perspective": { "scale": 1.0 } },
+            "focal_len": 1.0
+        }))
+        .unwrap();
+
+        SpriteRenderer {
+            shading_mode: ShadingModes::Normal,
+            image_width: 33,
+            image_height: 33,
+            camera_settings,
+            mesh_file: "res/cubes.obj".into(),
+            blacken_normal_map: true,
+            normal_map: NormalMapSettings::default(),
+            ambient_occlusion: AmbientOcclusionSettings::default(),
+            lighting: LightingSettings::default(),
+            sampling: SamplingSettings::default(),
+            pixel_art: PixelArtSettings::default(),
+            outline: None,
+            toon: None,
+        }
+    }
+
+    fn lit_renderer(eye: Vec3, target: Vec3, lights: Vec<Light>) -> SpriteRenderer {
+        let mut renderer = cubes_renderer_looking_at(eye, target);
+        renderer.shading_mode = ShadingModes::Lit;
+        renderer.sampling.passes.clear();
+        renderer.lighting = LightingSettings {
+            max_depth: 1,
+            lights,
+            sky: None,
+        };
+        renderer
+    }
+
+    fn white_light(direction: Vec3) -> Light {
+        Light {
+            direction,
+            color: Color::new(1., 1., 1., 1.),
+            intensity: 1.,
+        }
+    }
+
+    // Renders the map and maps the center pixel back to -1..1
+    fn center_normal(renderer: &SpriteRenderer) -> Vec3 {
+        let img = renderer.render().unwrap();
+        let pixel = img.get_pixel(16, 16);
+        let channel = |c: u8| (c as R) / 255. * 2. - 1.;
+
+        Vec3::new(channel(pixel[0]), channel(pixel[1]), channel(pixel[2]))
+    }
+
+    fn assert_near(expected: Vec3, actual: Vec3) {
+        let tolerance = 0.02;
+        assert!(
+            (expected - actual).len() < tolerance,
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+
+    fn view_space() -> NormalMapSettings {
+        NormalMapSettings {
+            space: NormalSpace::View,
+            ..NormalMapSettings::default()
+        }
+    }
+
+    #[test]
+    fn normal_map_world_space_returns_face_normal() {
+        let renderer = cubes_renderer(Vec3::new(5., 0., 0.), NormalMapSettings::default());
+
+        assert_near(Vec3::new(1., 0., 0.), center_normal(&renderer));
+    }
+
+    #[test]
+    fn normal_map_view_space_faces_camera() {
+        let renderer = cubes_renderer(Vec3::new(0., 0., 5.), view_space());
+        assert_near(Vec3::new(0., 0., 1.), center_normal(&renderer));
+
+        // Looking at the +x face from the side gives the same view space normal
+        let renderer = cubes_renderer(Vec3::new(5., 0., 0.), view_space());
+        assert_near(Vec3::new(0., 0., 1.), center_normal(&renderer));
+    }
+
+    #[test]
+    fn normal_map_view_space_tilts_away_from_elevated_camera() {
+        // The front face seen from above points down the screen
+        let len = (34. as R).sqrt();
+        let renderer = cubes_renderer(Vec3::new(0., 3., 5.), view_space());
+
+        assert_near(Vec3::new(0., -3. / len, 5. / len), center_normal(&renderer));
+    }
+
+    #[test]
+    fn normal_map_directx_flips_green() {
+        let len = (34. as R).sqrt();
+        let normal_map = NormalMapSettings {
+            handedness: Handedness::DirectX,
+            ..view_space()
+        };
+        let renderer = cubes_renderer(Vec3::new(0., 3., 5.), normal_map);
+
+        assert_near(Vec3::new(0., 3. / len, 5. / len), center_normal(&renderer));
+    }
+
+    #[test]
+    fn normal_map_swizzle_and_flip() {
+        let len = (34. as R).sqrt();
+        let normal_map = NormalMapSettings {
+            swizzle: [Axis::Z, Axis::Y, Axis::X],
+            flip_r: true,
+            ..view_space()
+        };
+        let renderer = cubes_renderer(Vec3::new(0., 3., 5.), normal_map);
+
+        assert_near(
+            Vec3::new(-5. / len, -3. / len, 0.),
+            center_normal(&renderer),
+        );
+    }
+
+    #[test]
+    fn depth_pass_is_brightest_closest_and_clear_on_miss() {
+        // Looking at the front face from slightly off center, so depth varies across the face
+        let mut renderer = cubes_renderer_looking_at(Vec3::new(3., 0., 5.), Vec3::new(0., 0., 0.));
+        renderer.shading_mode = ShadingModes::Depth;
+        let img = renderer.render().unwrap();
+
+        let hits: Vec<&Rgba<u8>> = img.pixels().filter(|p| p[3] > 0).collect();
+        assert!(hits.iter().any(|p| p[0] == 255));
+        assert!(hits.iter().any(|p| p[0] == 0));
+        assert_eq!(0, img.get_pixel(0, 0)[3]);
+    }
+
+    #[test]
+    fn mask_pass_covers_silhouette_only() {
+        let mut renderer = cubes_renderer_looking_at(Vec3::new(0., 0., 5.), Vec3::new(0., 0., 0.));
+        renderer.shading_mode = ShadingModes::Mask;
+        let img = renderer.render().unwrap();
+
+        assert_eq!(&Rgba([255, 255, 255, 255]), img.get_pixel(16, 16));
+        assert_eq!(&Rgba([0, 0, 0, 0]), img.get_pixel(0, 0));
+    }
+
+    #[test]
+    fn one_pixel_image_samples_the_view_center() {
+        let mut renderer = cubes_renderer_looking_at(Vec3::new(0., 0., 5.), Vec3::new(0., 0., 0.));
+        renderer.image_width = 1;
+        renderer.image_height = 1;
+        let img = renderer.render().unwrap();
+
+        assert_eq!(&Rgba([127, 127, 255, 255]), img.get_pixel(0, 0));
+    }
+
+    #[test]
+    fn material_id_pass_colors_by_material() {
+        let mut renderer = cubes_renderer_looking_at(Vec3::new(0., 0., 5.), Vec3::new(0., 0., 0.));
+        renderer.shading_mode = ShadingModes::MaterialId;
+        let img = renderer.render().unwrap();
+
+        let expected: [u8; 4] = id_color(0).into();
+        assert_eq!(&Rgba(expected), img.get_pixel(16, 16));
+        assert_eq!(0, img.get_pixel(0, 0)[3]);
+    }
+
+    #[test]
+    fn id_color_differs_for_neighbouring_ids() {
+        for id in 0..16 {
+            assert_ne!(id_color(id), id_color(id + 1));
+        }
+    }
+
+    #[test]
+    fn ambient_occlusion_pass_open_face_is_unoccluded() {
+        let mut renderer = cubes_renderer_looking_at(Vec3::new(0., 0., 5.), Vec3::new(0., 0., 0.));
+        renderer.shading_mode = ShadingModes::AmbientOcclusion;
+        renderer.ambient_occlusion.distance = 0.3;
+        let img = renderer.render().unwrap();
+
+        assert_eq!(&Rgba([255, 255, 255, 255]), img.get_pixel(16, 16));
+    }
+
+    #[test]
+    fn ambient_occlusion_pass_darkens_under_floating_cube() {
+        // The center ray lands on the top of the large cube, just under the small floating cube
+        let mut renderer = cubes_renderer_looking_at(Vec3::new(3., 1.5, 0.), Vec3::new(0., 1., 0.));
+        renderer.shading_mode = ShadingModes::AmbientOcclusion;
+        renderer.ambient_occlusion.distance = 10.;
+        let img = renderer.render().unwrap();
+
+        assert!(
+            img.get_pixel(16, 16)[0] < 128,
+            "{:?}",
+            img.get_pixel(16, 16)
+        );
+        // The occlusion rays are seeded, so the same pixels come out on every run
+        assert_eq!(img, renderer.render().unwrap());
+    }
+
+    #[test]
+    fn lit_pass_lights_faces_towards_light() {
+        let renderer = lit_renderer(
+            Vec3::new(0., 0., 5.),
+            Vec3::new(0., 0., 0.),
+            vec![white_light(Vec3::new(0., 0., 1.))],
+        );
+        let img = renderer.render().unwrap();
+
+        assert_eq!(&Rgba([0, 255, 255, 255]), img.get_pixel(16, 16));
+        assert_eq!(0, img.get_pixel(0, 0)[3]);
+    }
+
+    #[test]
+    fn lit_pass_faces_away_from_light_are_dark() {
+        let renderer = lit_renderer(
+            Vec3::new(0., 0., 5.),
+            Vec3::new(0., 0., 0.),
+            vec![white_light(Vec3::new(0., 0., -1.))],
+        );
+        let img = renderer.render().unwrap();
+
+        assert_eq!(&Rgba([0, 0, 0, 255]), img.get_pixel(16, 16));
+    }
+
+    #[test]
+    fn lit_pass_casts_shadows() {
+        // The top of the large cube is lit from straight above, but sits under the small floating cube
+        let renderer = lit_renderer(
+            Vec3::new(3., 1.5, 0.),
+            Vec3::new(0., 1., 0.),
+            vec![white_light(Vec3::new(0., 1., 0.))],
+        );
+        let img = renderer.render().unwrap();
+
+        assert_eq!(&Rgba([0, 0, 0, 255]), img.get_pixel(16, 16));
+    }
+
+    #[test]
+    fn lit_pass_bounces_pick_up_sky() {
+        let white = Color::new(1., 1., 1., 1.);
+        let mut renderer = lit_renderer(Vec3::new(0., 0., 5.), Vec3::new(0., 0., 0.), vec![]);
+        renderer.lighting.max_depth = 2;
+        renderer.sampling.default = Sampling {
+            pattern: SamplePattern::Jittered,
+            grid_size: 4,
+            hard_edges: false,
+        };
+        renderer.lighting.sky = Some(Sky {
+            top: white,
+            bottom: white,
+        });
+        let img = renderer.render().unwrap();
+
+        let pixel = img.get_pixel(16, 16);
+        assert_eq!(0, pixel[0]);
+        assert!(pixel[1] > 128, "{:?}", pixel);
+        // The bounces and the jittered samples are seeded, so the same pixels come out on every run
+        assert_eq!(img, renderer.render().unwrap());
+    }
+
+    #[test]
+    fn lit_pass_antialiases_silhouette() {
+        let mut renderer = lit_renderer(
+            Vec3::new(0., 0., 5.),
+            Vec3::new(0., 0., 0.),
+            vec![white_light(Vec3::new(0., 0., 1.))],
+        );
+        renderer.sampling.default = Sampling {
+            pattern: SamplePattern::Jittered,
+            grid_size: 8,
+            hard_edges: false,
+        };
+        let img = renderer.render().unwrap();
+
+        assert!(img.pixels().any(|p| p[3] > 0 && p[3] < 255));
+    }
+
+    fn grid(hard_edges: bool) -> Sampling {
+        Sampling {
+            pattern: SamplePattern::Grid,
+            grid_size: 4,
+            hard_edges,
+        }
+    }
+
+    #[test]
+    fn supersampling_gives_fractional_edge_alpha() {
+        let mut renderer = cubes_renderer_looking_at(Vec3::new(0., 0., 5.), Vec3::new(0., 0., 0.));
+        renderer.shading_mode = ShadingModes::Mask;
+        renderer.sampling.default = grid(false);
+        let img = renderer.render().unwrap();
+
+        assert!(img.pixels().any(|p| p[3] > 0 && p[3] < 255));
+        assert_eq!(255, img.get_pixel(16, 16)[3]);
+    }
+
+    #[test]
+    fn supersampling_hard_edges_keeps_binary_alpha() {
+        let mut renderer = cubes_renderer_looking_at(Vec3::new(0., 0., 5.), Vec3::new(0., 0., 0.));
+        renderer.shading_mode = ShadingModes::Mask;
+        renderer.sampling.default = grid(true);
+        let img = renderer.render().unwrap();
+
+        assert!(img.pixels().all(|p| p[3] == 0 || p[3] == 255));
+        assert!(img.pixels().any(|p| p[3] == 255));
+    }
+
+    #[test]
+    fn supersampling_normals_stay_unit_length() {
+        // Looking at a corner of the big cube, so pixels along the edges average different faces
+        let mut renderer = cubes_renderer_looking_at(Vec3::new(4., 0., 4.), Vec3::new(0., 0., 0.));
+        renderer.sampling.default = grid(false);
+        renderer.blacken_normal_map = false;
+        let img = renderer.render().unwrap();
+
+        let channel = |c: u8| (c as R) / 255. * 2. - 1.;
+        for p in img.pixels().filter(|p| p[3] > 0) {
+            let normal = Vec3::new(channel(p[0]), channel(p[1]), channel(p[2]));
+            assert!((normal.len() - 1.).abs() < 0.02, "{:?}", normal);
+        }
+    }
+
+    #[test]
+    fn supersampling_does_not_blend_material_ids() {
+        let mut renderer = cubes_renderer_looking_at(Vec3::new(0., 0., 5.), Vec3::new(0., 0., 0.));
+        renderer.shading_mode = ShadingModes::MaterialId;
+        renderer.sampling.default = grid(false);
+        let img = renderer.render().unwrap();
+
+        let expected: [u8; 4] = id_color(0).into();
+        for p in img.pixels().filter(|p| p[3] > 0) {
+            assert_eq!(expected[..3], p.0[..3]);
+        }
+    }
+
+    // A render at a larger scale covers the same view, so the pixels `select` picks out can only
+    // differ along the edge of the ones picked out in the unscaled render
+    fn assert_same_away_from_edges(
+        unscaled: &RgbaImage,
+        scaled: &RgbaImage,
+        select: fn(&Rgba<u8>) -> bool,
+    ) {
+        assert_eq!(unscaled.dimensions(), scaled.dimensions());
+        let (width, height) = (unscaled.width() as i64, unscaled.height() as i64);
+        let selected = |x: i64, y: i64| {
+            x >= 0
+                && y >= 0
+                && x < width
+                && y < height
+                && select(unscaled.get_pixel(x as u32, y as u32))
+        };
+
+        for (x, y, p) in scaled.enumerate_pixels() {
+            let (x, y) = (x as i64, y as i64);
+            if select(p) != selected(x, y) {
+                let on_edge = (-1..=1)
+                    .flat_map(|dy| (-1..=1).map(move |dx| (x + dx, y + dy)))
+                    .any(|(nx, ny)| selected(nx, ny) != selected(x, y));
+                assert!(on_edge, "({}, {}) differs away from the edge", x, y);
             }
-            ShadingModes::Normal => {
-                // Now that we have the normal, orient it to the camera.
-                // map x,y,z from -1..1 to 0..1
-                // println!("Normal: {:?}", hr.normal);
+        }
+    }
+
+    #[test]
+    fn pixel_art_renders_at_scale_and_downsamples() {
+        let mut renderer = cubes_renderer_looking_at(Vec3::new(0., 0., 5.), Vec3::new(0., 0., 0.));
+        renderer.shading_mode = ShadingModes::Mask;
+        let unscaled = renderer.render().unwrap();
+        renderer.pixel_art.scale = 4;
+        renderer.pixel_art.filter = pixel_art::DownsampleFilter::Majority;
+        let img = renderer.render().unwrap();
+
+        assert_eq!((33, 33), img.dimensions());
+        assert_same_away_from_edges(&unscaled, &img, |p| p[3] > 0);
+    }
+
+    #[test]
+    fn pixel_art_quantizes_color_passes_only() {
+        let mut renderer = cubes_renderer_looking_at(Vec3::new(0., 0., 5.), Vec3::new(0., 0., 0.));
+        renderer.pixel_art.palette_file = Some("res/pico-8.hex".into());
+        let palette = Palette::from_file("res/pico-8.hex").unwrap();
+
+        renderer.shading_mode = ShadingModes::Diffuse;
+        let img = renderer.render().unwrap();
+        let quantized = pixel_art::quantize(&img, &palette, false);
+        assert_eq!(quantized, img);
+
+        renderer.shading_mode = ShadingModes::Normal;
+        let img = renderer.render().unwrap();
+        let quantized = pixel_art::quantize(&img, &palette, false);
+        assert_ne!(quantized, img);
+    }
+
+    #[test]
+    fn pixel_art_missing_palette_returns_err() {
+        let mut renderer = cubes_renderer_looking_at(Vec3::new(0., 0., 5.), Vec3::new(0., 0., 0.));
+        renderer.shading_mode = ShadingModes::Diffuse;
+        renderer.pixel_art.palette_file = Some("res/does_not_exist.hex".into());
+
+        match renderer.render() {
+            Err(RenderError::Palette(PaletteError::Io(_, _))) => {}
+            _ => panic!("expected a palette error"),
+        }
+    }
+
+    #[test]
+    fn outline_draws_around_color_passes_only() {
+        let mut renderer = cubes_renderer_looking_at(Vec3::new(0., 0., 5.), Vec3::new(0., 0., 0.));
+        renderer.outline = Some(OutlineSettings {
+            color: Color::new(1., 0., 0., 1.),
+            ..OutlineSettings::default()
+        });
+
+        renderer.shading_mode = ShadingModes::Diffuse;
+        let img = renderer.render().unwrap();
+        assert!(img.pixels().any(|p| p == &Rgba([255, 0, 0, 255])));
+        assert_ne!(&Rgba([255, 0, 0, 255]), img.get_pixel(16, 16));
+
+        renderer.shading_mode = ShadingModes::Mask;
+        let img = renderer.render().unwrap();
+        assert!(!img.pixels().any(|p| p == &Rgba([255, 0, 0, 255])));
+    }
+
+    #[test]
+    fn outline_lines_up_with_scaled_sprite() {
+        let mut renderer = cubes_renderer_looking_at(Vec3::new(4., 1., 4.), Vec3::new(0., 0., 0.));
+        renderer.shading_mode = ShadingModes::Diffuse;
+        renderer.outline = Some(OutlineSettings {
+            color: Color::new(1., 0., 0., 1.),
+            ..OutlineSettings::default()
+        });
+        let unscaled = renderer.render().unwrap();
+        renderer.pixel_art.scale = 3;
+        let img = renderer.render().unwrap();
+
+        let is_line = |p: &Rgba<u8>| p == &Rgba([255, 0, 0, 255]);
+        assert!(img.pixels().any(is_line));
+        assert_same_away_from_edges(&unscaled, &img, is_line);
+        assert_same_away_from_edges(&unscaled, &img, |p| p[3] > 0);
+    }
+
+    #[test]
+    fn toon_bands_lit_pass() {
+        let mut renderer = lit_renderer(
+            Vec3::new(4., 1., 4.),
+            Vec3::new(0., 0., 0.),
+            vec![white_light(Vec3::new(-1., 2., 3.))],
+        );
+        renderer.toon = Some(ToonSettings { bands: 2 });
+        let img = renderer.render().unwrap();
+
+        let mut greens: Vec<u8> = img.pixels().filter(|p| p[3] > 0).map(|p| p[1]).collect();
+        greens.sort();
+        greens.dedup();
+        assert!(greens.len() <= 3, "{:?}", greens);
+    }
+
+    fn auto_framed_renderer(eye: Vec3) -> SpriteRenderer {
+        let mut renderer = cubes_renderer_looking_at(eye, Vec3::new(0., 0., 0.));
+        renderer.shading_mode = ShadingModes::Mask;
+        renderer.camera_settings = serde_json::from_value(serde_json::json!({
+            "eye": eye,
+            "auto_frame": { "padding": 0.1 }
+        }))
+        .unwrap();
+        renderer
+    }
 
-                let normal = (hr.normal + Vec3::one()) / 2.;
+    #[test]
+    fn auto_frame_fits_mesh_in_image() {
+        let img = auto_framed_renderer(Vec3::new(40., 25., 60.))
+            .render()
+            .unwrap();
 
-                return Color::from_vec3(normal, 1.);
+        let (mut min, mut max) = ((33, 33), (0, 0));
+        for (x, y, p) in img.enumerate_pixels() {
+            if p[3] > 0 {
+                min = (min.0.min(x), min.1.min(y));
+                max = (max.0.max(x), max.1.max(y));
             }
-        },
-        None => {}
+        }
+
+        // Nothing is cut off, and the mesh isn't a speck in the middle
+        assert!(min.0 > 0 && min.1 > 0 && max.0 < 32 && max.1 < 32);
+        assert!(max.0 - min.0 >= 16 || max.1 - min.1 >= 16);
+    }
+
+    #[test]
+    fn auto_frame_ignores_eye_distance() {
+        let near = auto_framed_renderer(Vec3::new(0., 1., 3.))
+            .render()
+            .unwrap();
+        let far = auto_framed_renderer(Vec3::new(0., 10., 30.))
+            .render()
+            .unwrap();
+
+        assert_eq!(near, far);
+    }
+
+    // An axis aligned cube from the origin to (size, size, size), as a mesh file in `dir`
+    fn cube_mesh(dir: &std::path::Path, size: u32) -> String {
+        let mesh_file = dir.join(format!("cube_{}.obj", size));
+        let s = size;
+        std::fs::write(
+            &mesh_file,
+            format!(
+                "v 0 0 0\nv {s} 0 0\nv {s} {s} 0\nv 0 {s} 0\nv 0 0 {s}\nv {s} 0 {s}\nv {s} {s} {s}\nv 0 {s} {s}\n\
+                 f 5 6 7 8\nf 1 4 3 2\nf 1 5 8 4\nf 2 3 7 6\nf 4 8 7 3\nf 1 2 6 5\n",
+                s = s
+            ),
+        )
+        .unwrap();
+
+        mesh_file.to_string_lossy().into_owned()
+    }
+
+    fn side_view_renderer(
+        mesh_file: String,
+        target: Vec3,
+        width: u32,
+        height: u32,
+    ) -> SpriteRenderer {
+        let mut renderer = cubes_renderer_looking_at(Vec3::new(0., 0., 5.), target);
+        renderer.mesh_file = mesh_file;
+        renderer.shading_mode = ShadingModes::Mask;
+        renderer.image_width = width;
+        renderer.image_height = height;
+        renderer.camera_settings = serde_json::from_value(serde_json::json!({
+            "target": target,
+            "projection": { "preset": "SideView", "tile_size": 1.0, "tile_width": 8 }
+        }))
+        .unwrap();
+        renderer
+    }
+
+    fn covered_pixels(img: &RgbaImage) -> Vec<(u32, u32)> {
+        img.enumerate_pixels()
+            .filter(|(_, _, p)| p[3] > 0)
+            .map(|(x, y, _)| (x, y))
+            .collect()
+    }
+
+    #[test]
+    fn side_view_projection_draws_tile_sized_cube_pixel_exact() {
+        let dir = crate::test_util::temp_dir("unit_cube");
+        let renderer = side_view_renderer(cube_mesh(&dir, 1), Vec3::new(0.3, 0.6, 0.), 32, 32);
+        let img = renderer.render().unwrap();
+
+        // The target snaps to the nearest pixel corner at (0.25, 0.625), which puts the cube's
+        // corner 2 pixels left of and 5 below the image center. It covers exactly 8x8 pixels.
+        let covered = covered_pixels(&img);
+        assert_eq!(64, covered.len());
+        assert!(
+            covered
+                .iter()
+                .all(|(x, y)| (14..22).contains(x) && (13..21).contains(y)),
+            "{:?}",
+            covered
+        );
+    }
+
+    #[test]
+    fn side_view_projection_is_pixel_exact_at_any_image_size() {
+        let dir = crate::test_util::temp_dir("large_cube");
+        let mesh_file = cube_mesh(&dir, 4);
+
+        // From an image the cube fills edge to edge, to one with room all around it
+        for (width, height) in [(32, 32), (34, 32), (32, 40), (64, 64)].iter() {
+            let renderer =
+                side_view_renderer(mesh_file.clone(), Vec3::new(2., 2., 2.), *width, *height);
+            let img = renderer.render().unwrap();
+
+            // 4 units at 8 pixels a unit, whatever the size of the image around it
+            let covered = covered_pixels(&img);
+            let min = covered
+                .iter()
+                .fold((*width, *height), |m, p| (m.0.min(p.0), m.1.min(p.1)));
+            let max = covered
+                .iter()
+                .fold((0, 0), |m, p| (m.0.max(p.0), m.1.max(p.1)));
+            assert_eq!(
+                (32 * 32, (31, 31)),
+                (covered.len(), (max.0 - min.0, max.1 - min.1)),
+                "{}x{}",
+                width,
+                height
+            );
+        }
     }
 
-    Color::new(0., 0., 0., 0.)
+    #[test]
+    fn orthographic_camera_sees_mesh_from_off_origin_eye() {
+        let mut renderer = cubes_renderer_looking_at(Vec3::new(0., 0., 5.), Vec3::new(0., 0., 0.));
+        renderer.shading_mode = ShadingModes::Mask;
+        renderer.camera_settings = serde_json::from_value(serde_json::json!({
+            "eye": Vec3::new(6., 1.64, 6.),
+            "target": Vec3::new(0., 1.64, 0.),
+            "perspective": { "Orthographic": { "width": 2.0 } }
+        }))
+        .unwrap();
+        let img = renderer.render().unwrap();
+
+        // The small cube on top fills the center, with the big cube below it at the bottom edge
+        assert_eq!(255, img.get_pixel(16, 16)[3]);
+        assert_eq!(255, img.get_pixel(16, 32)[3]);
+        assert_eq!(0, img.get_pixel(0, 0)[3]);
+        assert_eq!(0, img.get_pixel(32, 0)[3]);
+    }
 }
@@ -0,0 +1,77 @@
+use super::camera::Camera;
+use super::math::{Vec3, R};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
+pub enum NormalSpace {
+    World,
+    View,
+}
+
+// Which way green points in the output. OpenGL and SpriteIlluminator use Y+ (up), DirectX uses Y- (down).
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
+pub enum Handedness {
+    OpenGl,
+    DirectX,
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct NormalMapSettings {
+    pub space: NormalSpace,
+    pub handedness: Handedness,
+    // Source axis written to the r, g and b channels
+    pub swizzle: [Axis; 3],
+    pub flip_r: bool,
+    pub flip_g: bool,
+    pub flip_b: bool,
+}
+
+impl Default for NormalMapSettings {
+    fn default() -> Self {
+        Self {
+            space: NormalSpace::World,
+            handedness: Handedness::OpenGl,
+            swizzle: [Axis::X, Axis::Y, Axis::Z],
+            flip_r: false,
+            flip_g: false,
+            flip_b: false,
+        }
+    }
+}
+
+impl NormalMapSettings {
+    // Returns the normal as it should be written to the map, still in -1..1
+    pub fn encode(&self, normal: Vec3, camera: &Camera) -> Vec3 {
+        let mut n = match self.space {
+            NormalSpace::World => normal,
+            NormalSpace::View => camera.to_view_space(normal),
+        };
+
+        if self.handedness == Handedness::DirectX {
+            n.y = -n.y;
+        }
+
+        let axis = |a: Axis| match a {
+            Axis::X => n.x,
+            Axis::Y => n.y,
+            Axis::Z => n.z,
+        };
+
+        let flip = |flipped: bool, v: R| if flipped { -v } else { v };
+
+        Vec3::new(
+            flip(self.flip_r, axis(self.swizzle[0])),
+            flip(self.flip_g, axis(self.swizzle[1])),
+            flip(self.flip_b, axis(self.swizzle[2])),
+        )
+    }
+}
@@ -0,0 +1,347 @@
+use image::{ImageBuffer, Rgba, RgbaImage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::Path;
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+pub enum DownsampleFilter {
+    // Center pixel of each block
+    Nearest,
+    // Most common color in each block, with fully transparent pixels counted as one color
+    Majority,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct PixelArtSettings {
+    // Passes are rendered at scale times the image size, then downsampled
+    pub scale: u32,
+    pub filter: DownsampleFilter,
+    // .hex or .gpl file the color passes are quantized to
+    pub palette_file: Option<String>,
+    pub dither: bool,
+}
+
+impl Default for PixelArtSettings {
+    fn default() -> Self {
+        Self {
+            scale: 1,
+            filter: DownsampleFilter::Nearest,
+            palette_file: None,
+            dither: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum PaletteError {
+    Io(String, std::io::Error),
+    UnknownFormat(String),
+    InvalidLine(usize, String),
+    Empty,
+}
+
+impl std::fmt::Display for PaletteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PaletteError::Io(file, e) => write!(f, "unable to read palette '{}': {}", file, e),
+            PaletteError::UnknownFormat(file) => {
+                write!(f, "palette '{}' must be a .hex or .gpl file", file)
+            }
+            PaletteError::InvalidLine(line, contents) => {
+                write!(f, "invalid palette color on line {}: '{}'", line, contents)
+            }
+            PaletteError::Empty => write!(f, "palette contains no colors"),
+        }
+    }
+}
+
+impl std::error::Error for PaletteError {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Palette {
+    colors: Vec<[u8; 3]>,
+}
+
+impl Palette {
+    pub fn from_file(file: &str) -> Result<Self, PaletteError> {
+        let mut contents = String::new();
+        File::open(Path::new(file))
+            .and_then(|mut f| f.read_to_string(&mut contents))
+            .map_err(|e| PaletteError::Io(file.into(), e))?;
+
+        let extension = Path::new(file)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase());
+
+        match extension.as_deref() {
+            Some("hex") => Self::from_hex(&contents),
+            Some("gpl") => Self::from_gpl(&contents),
+            _ => Err(PaletteError::UnknownFormat(file.into())),
+        }
+    }
+
+    // One RRGGBB color per line, as exported by Lospec
+    pub fn from_hex(contents: &str) -> Result<Self, PaletteError> {
+        let mut colors = vec![];
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let hex = line.trim_start_matches('#');
+            let channel = |start: usize| {
+                hex.get(start..start + 2)
+                    .and_then(|c| u8::from_str_radix(c, 16).ok())
+            };
+
+            match (hex.len(), channel(0), channel(2), channel(4)) {
+                (6, Some(r), Some(g), Some(b)) => colors.push([r, g, b]),
+                _ => return Err(PaletteError::InvalidLine(i + 1, line.into())),
+            }
+        }
+
+        Self::new(colors)
+    }
+
+    // GIMP palette. A header, then one "R G B name" color per line.
+    pub fn from_gpl(contents: &str) -> Result<Self, PaletteError> {
+        let mut colors = vec![];
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty()
+                || line.starts_with('#')
+                || line.starts_with("GIMP Palette")
+                || line.starts_with("Name:")
+                || line.starts_with("Columns:")
+            {
+                continue;
+            }
+
+            let channels: Vec<Option<u8>> = line
+                .split_whitespace()
+                .take(3)
+                .map(|c| c.parse().ok())
+                .collect();
+
+            match channels.as_slice() {
+                [Some(r), Some(g), Some(b)] => colors.push([*r, *g, *b]),
+                _ => return Err(PaletteError::InvalidLine(i + 1, line.into())),
+            }
+        }
+
+        Self::new(colors)
+    }
+
+    fn new(colors: Vec<[u8; 3]>) -> Result<Self, PaletteError> {
+        if colors.is_empty() {
+            return Err(PaletteError::Empty);
+        }
+
+        Ok(Self { colors })
+    }
+
+    fn nearest(&self, r: f32, g: f32, b: f32) -> [u8; 3] {
+        let distance = |c: &[u8; 3]| {
+            let dr = c[0] as f32 - r;
+            let dg = c[1] as f32 - g;
+            let db = c[2] as f32 - b;
+            dr * dr + dg * dg + db * db
+        };
+
+        let mut best = self.colors[0];
+        for c in self.colors.iter() {
+            if distance(c) < distance(&best) {
+                best = *c;
+            }
+        }
+
+        best
+    }
+}
+
+pub fn downsample(img: &RgbaImage, scale: u32, filter: DownsampleFilter) -> RgbaImage {
+    if scale <= 1 {
+        return img.clone();
+    }
+
+    let width = img.width() / scale;
+    let height = img.height() / scale;
+
+    ImageBuffer::from_fn(width, height, |x, y| {
+        let x0 = x * scale;
+        let y0 = y * scale;
+
+        match filter {
+            DownsampleFilter::Nearest => *img.get_pixel(x0 + scale / 2, y0 + scale / 2),
+            DownsampleFilter::Majority => {
+                let mut block = vec![];
+                for j in y0..y0 + scale {
+                    for i in x0..x0 + scale {
+                        let p = *img.get_pixel(i, j);
+                        block.push(if p[3] == 0 { Rgba([0, 0, 0, 0]) } else { p });
+                    }
+                }
+
+                let mut best = block[0];
+                let mut best_count = 0;
+                for p in block.iter() {
+                    let count = block.iter().filter(|other| *other == p).count();
+                    if count > best_count {
+                        best = *p;
+                        best_count = count;
+                    }
+                }
+
+                best
+            }
+        }
+    })
+}
+
+// 4x4 Bayer matrix for ordered dithering
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+pub fn quantize(img: &RgbaImage, palette: &Palette, dither: bool) -> RgbaImage {
+    // Roughly the distance between neighbouring palette colors, so the dither can reach them
+    let spread = 255. / (palette.colors.len() as f32).cbrt();
+
+    ImageBuffer::from_fn(img.width(), img.height(), |x, y| {
+        let p = img.get_pixel(x, y);
+        if p[3] == 0 {
+            return *p;
+        }
+
+        let offset = if dither {
+            let threshold = BAYER[(y % 4) as usize][(x % 4) as usize] as f32;
+            ((threshold + 0.5) / 16. - 0.5) * spread
+        } else {
+            0.
+        };
+
+        let c = palette.nearest(
+            p[0] as f32 + offset,
+            p[1] as f32 + offset,
+            p[2] as f32 + offset,
+        );
+
+        Rgba([c[0], c[1], c[2], p[3]])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bw() -> Palette {
+        Palette::from_hex("000000\nFFFFFF\n").unwrap()
+    }
+
+    #[test]
+    fn palette_from_hex_parses_colors() {
+        let palette = Palette::from_hex("#1d2b53\n7E2553\n\n").unwrap();
+
+        assert_eq!(vec![[0x1d, 0x2b, 0x53], [0x7e, 0x25, 0x53]], palette.colors);
+    }
+
+    #[test]
+    fn palette_from_hex_invalid_line_returns_err() {
+        match Palette::from_hex("000000\nnope\n") {
+            Err(PaletteError::InvalidLine(2, _)) => {}
+            _ => panic!("expected an invalid line error"),
+        }
+    }
+
+    #[test]
+    fn palette_from_gpl_parses_colors() {
+        let gpl =
+            "GIMP Palette\nName: Test\nColumns: 2\n#\n  0   0   0\tBlack\n255 128 64 Orange\n";
+        let palette = Palette::from_gpl(gpl).unwrap();
+
+        assert_eq!(vec![[0, 0, 0], [255, 128, 64]], palette.colors);
+    }
+
+    #[test]
+    fn palette_empty_returns_err() {
+        match Palette::from_gpl("GIMP Palette\n") {
+            Err(PaletteError::Empty) => {}
+            _ => panic!("expected an empty palette error"),
+        }
+    }
+
+    #[test]
+    fn palette_from_file_loads_res_palette() {
+        let palette = Palette::from_file("res/pico-8.hex").unwrap();
+
+        assert_eq!(16, palette.colors.len());
+    }
+
+    #[test]
+    fn downsample_nearest_takes_block_center() {
+        let img = ImageBuffer::from_fn(4, 4, |x, y| Rgba([(x * 10 + y) as u8, 0, 0, 255]));
+        let actual = downsample(&img, 2, DownsampleFilter::Nearest);
+
+        assert_eq!((2, 2), actual.dimensions());
+        assert_eq!(&Rgba([11, 0, 0, 255]), actual.get_pixel(0, 0));
+        assert_eq!(&Rgba([33, 0, 0, 255]), actual.get_pixel(1, 1));
+    }
+
+    #[test]
+    fn downsample_majority_takes_most_common() {
+        let red = Rgba([255, 0, 0, 255]);
+        let mut img = ImageBuffer::from_pixel(3, 3, red);
+        img.put_pixel(1, 1, Rgba([0, 0, 255, 255]));
+        img.put_pixel(0, 0, Rgba([9, 9, 9, 0]));
+        let actual = downsample(&img, 3, DownsampleFilter::Majority);
+
+        assert_eq!(&red, actual.get_pixel(0, 0));
+
+        // Transparent pixels all count as the same color, regardless of rgb
+        let mut img = ImageBuffer::from_pixel(2, 2, Rgba([1, 2, 3, 0]));
+        img.put_pixel(0, 0, red);
+        img.put_pixel(1, 1, Rgba([4, 5, 6, 0]));
+        let actual = downsample(&img, 2, DownsampleFilter::Majority);
+
+        assert_eq!(&Rgba([0, 0, 0, 0]), actual.get_pixel(0, 0));
+    }
+
+    #[test]
+    fn quantize_maps_to_palette() {
+        let img = ImageBuffer::from_fn(2, 1, |x, _| {
+            if x == 0 {
+                Rgba([40, 60, 20, 255])
+            } else {
+                Rgba([200, 220, 180, 128])
+            }
+        });
+        let actual = quantize(&img, &bw(), false);
+
+        assert_eq!(&Rgba([0, 0, 0, 255]), actual.get_pixel(0, 0));
+        assert_eq!(&Rgba([255, 255, 255, 128]), actual.get_pixel(1, 0));
+    }
+
+    #[test]
+    fn quantize_skips_transparent_pixels() {
+        let img = ImageBuffer::from_pixel(1, 1, Rgba([40, 60, 20, 0]));
+        let actual = quantize(&img, &bw(), false);
+
+        assert_eq!(&Rgba([40, 60, 20, 0]), actual.get_pixel(0, 0));
+    }
+
+    #[test]
+    fn quantize_dither_mixes_colors_on_mid_gray() {
+        let img = ImageBuffer::from_pixel(4, 4, Rgba([128, 128, 128, 255]));
+
+        let flat = quantize(&img, &bw(), false);
+        let first = flat.get_pixel(0, 0);
+        assert!(flat.pixels().all(|p| p == first));
+
+        let dithered = quantize(&img, &bw(), true);
+        let white = dithered.pixels().filter(|p| p[0] == 255).count();
+        assert_eq!(8, white);
+    }
+}
@@ -1,24 +0,0 @@
-use super::math::{Vec3, R};
-
-pub struct Ray {
-    origin: Vec3,
-    direction: Vec3,
-}
-
-impl Ray {
-    pub fn new(origin: Vec3, direction: Vec3) -> Self {
-        Self { origin, direction }
-    }
-
-    pub fn origin(&self) -> Vec3 {
-        self.origin
-    }
-
-    pub fn direction(&self) -> Vec3 {
-        self.direction
-    }
-
-    pub fn at(&self, t: R) -> Vec3 {
-        self.origin + t * self.direction
-    }
-}
@@ -0,0 +1,155 @@
+use super::math::{random_normalized, R};
+use super::ShadingModes;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+pub enum SamplePattern {
+    // A single ray through the pixel center. Hard edges, no anti-aliasing.
+    Center,
+    // Evenly spaced grid_size x grid_size rays
+    Grid,
+    // One ray at a random position in each cell of a grid_size x grid_size grid
+    Jittered,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct Sampling {
+    pub pattern: SamplePattern,
+    pub grid_size: u32,
+    // Rounds coverage to fully opaque or fully transparent, for pixel art
+    pub hard_edges: bool,
+}
+
+impl Default for Sampling {
+    fn default() -> Self {
+        Self {
+            pattern: SamplePattern::Center,
+            grid_size: 1,
+            hard_edges: false,
+        }
+    }
+}
+
+impl Sampling {
+    // Offsets from the pixel center, in pixels
+    pub fn offsets(&self) -> Vec<(R, R)> {
+        let n = self.grid_size.max(1);
+        if self.pattern == SamplePattern::Center {
+            return vec![(0., 0.)];
+        }
+
+        let cell = 1. / (n as R);
+        let mut offsets = vec![];
+        for y in 0..n {
+            for x in 0..n {
+                let (jx, jy) = match self.pattern {
+                    SamplePattern::Jittered => (random_normalized(), random_normalized()),
+                    _ => (0.5, 0.5),
+                };
+
+                let u = ((x as R) + jx) * cell - 0.5;
+                let v = ((y as R) + jy) * cell - 0.5;
+                offsets.push((u, v));
+            }
+        }
+
+        offsets
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct SamplingSettings {
+    pub default: Sampling,
+    // Per pass overrides of the default
+    pub passes: HashMap<ShadingModes, Sampling>,
+}
+
+impl Default for SamplingSettings {
+    fn default() -> Self {
+        let mut passes = HashMap::new();
+        // The lit pass needs many samples to smooth out the path tracing noise
+        passes.insert(
+            ShadingModes::Lit,
+            Sampling {
+                pattern: SamplePattern::Jittered,
+                grid_size: 4,
+                hard_edges: false,
+            },
+        );
+
+        Self {
+            default: Sampling::default(),
+            passes,
+        }
+    }
+}
+
+impl SamplingSettings {
+    pub fn for_pass(&self, pass: ShadingModes) -> Sampling {
+        match self.passes.get(&pass) {
+            Some(sampling) => *sampling,
+            None => self.default,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sampling(pattern: SamplePattern, grid_size: u32) -> Sampling {
+        Sampling {
+            pattern,
+            grid_size,
+            hard_edges: false,
+        }
+    }
+
+    #[test]
+    fn sampling_center_returns_single_offset() {
+        let offsets = sampling(SamplePattern::Center, 4).offsets();
+
+        assert_eq!(vec![(0., 0.)], offsets);
+    }
+
+    #[test]
+    fn sampling_grid_returns_cell_centers() {
+        let offsets = sampling(SamplePattern::Grid, 2).offsets();
+
+        assert_eq!(
+            vec![(-0.25, -0.25), (0.25, -0.25), (-0.25, 0.25), (0.25, 0.25)],
+            offsets
+        );
+    }
+
+    #[test]
+    fn sampling_jittered_stays_in_cells() {
+        let n = 3;
+        let offsets = sampling(SamplePattern::Jittered, n).offsets();
+
+        assert_eq!((n * n) as usize, offsets.len());
+        for (i, (u, v)) in offsets.iter().enumerate() {
+            let x = (i as u32 % n) as R;
+            let y = (i as u32 / n) as R;
+            let cell = 1. / (n as R);
+
+            assert!(*u >= x * cell - 0.5 && *u <= (x + 1.) * cell - 0.5);
+            assert!(*v >= y * cell - 0.5 && *v <= (y + 1.) * cell - 0.5);
+        }
+    }
+
+    #[test]
+    fn sampling_settings_uses_pass_override() {
+        let settings = SamplingSettings::default();
+
+        assert_eq!(Sampling::default(), settings.for_pass(ShadingModes::Normal));
+        assert_eq!(
+            SamplePattern::Jittered,
+            settings.for_pass(ShadingModes::Lit).pattern
+        );
+    }
+}